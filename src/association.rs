@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use crate::bits::Bits;
+use crate::codec::{add_fill_bits, Encodable, FillBitCapacity, Writer};
+use crate::fragmentation::Fragmenter;
+use crate::pdu::downlink::{MACResourcePDU, NULL_PDU_SIZE};
+
+/// Associates several MAC-RESOURCE PDUs in a single MAC block, according to ETSI EN 300 392-2
+/// § 23.4.2.3
+///
+/// Each PDU indicates its length in octets, so the next begins on the following octet boundary.
+/// Once no more PDUs are to be added, the block is completed with a Null PDU if there's space for
+/// one, then fill bits.
+pub(crate) struct MACBlockBuilder {
+    bits: Bits,
+    capacity: usize
+}
+
+impl MACBlockBuilder {
+
+    /// Start building a MAC block of `capacity` bits
+    pub(crate) fn new(capacity: usize) -> Self {
+        MACBlockBuilder {
+            bits: Bits::new(),
+            capacity
+        }
+    }
+
+    /// The number of bits remaining in the block
+    pub(crate) fn remaining(&self) -> usize {
+        self.capacity - self.bits.len()
+    }
+
+    /// Add a PDU carrying a whole TM-SDU, setting its length indication and fill bit indication.
+    /// If it doesn't fit in the rest of the block, the PDU is returned.
+    pub(crate) fn push(&mut self, pdu: MACResourcePDU) -> Result<(), MACResourcePDU> {
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        if writer.done().len() > self.remaining() {
+            return Err(pdu);
+        }
+
+        // The TM-SDU fits, so it won't be fragmented
+        let pdu_bits = Fragmenter::new(pdu)
+            .next_pdu(self.remaining())
+            .expect("a new fragmenter always has a PDU");
+        self.bits.extend_from_bitslice(&pdu_bits);
+
+        Ok(())
+    }
+
+    /// Add the next PDU of a fragmented TM-SDU, which fills the block unless it's the last
+    pub(crate) fn push_fragment(&mut self, fragmenter: &mut Fragmenter) {
+        if let Some(pdu_bits) = fragmenter.next_pdu(self.remaining()) {
+            self.bits.extend_from_bitslice(&pdu_bits);
+        }
+    }
+
+    /// Complete the block with a Null PDU and/or fill bits
+    pub(crate) fn finish(mut self) -> Bits {
+
+        let remaining = self.remaining();
+        if remaining >= NULL_PDU_SIZE {
+            let null_pdu = MACResourcePDU {
+                fill_bit_indication: remaining > NULL_PDU_SIZE,
+                ..MACResourcePDU::null()
+            };
+            let mut writer = Writer::new();
+            null_pdu.encode(&mut writer);
+            self.bits.extend_from_bitslice(&writer.done());
+        }
+
+        add_fill_bits(&mut self.bits, FillBitCapacity::Bits(self.capacity));
+
+        self.bits
+    }
+}
+
+/// Build a MAC block of `capacity` bits from as many of the queued PDUs as fit, in order
+pub(crate) fn associate(queue: &mut VecDeque<MACResourcePDU>, capacity: usize) -> Bits {
+
+    let mut builder = MACBlockBuilder::new(capacity);
+
+    while let Some(pdu) = queue.pop_front() {
+        if let Err(pdu) = builder.push(pdu) {
+            queue.push_front(pdu);
+            break;
+        }
+    }
+
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::pdu::downlink::{Address, DownlinkMACPDU, Length};
+
+    fn pdu(address: u32, tm_sdu_size: usize) -> MACResourcePDU {
+        MACResourcePDU {
+            address: Address::SSI { address },
+            tm_sdu_bits: Bits::repeat(true, tm_sdu_size),
+            ..MACResourcePDU::null()
+        }
+    }
+
+    #[test]
+    fn associates_and_dissociates() {
+
+        let mut queue = VecDeque::from([pdu(1, 13), pdu(2, 5), pdu(3, 40)]);
+
+        // The third PDU doesn't fit on the SCH/HD after the first two
+        let block = associate(&mut queue, 124);
+        assert_eq!(block.len(), 124);
+        assert_eq!(queue.len(), 1);
+
+        let pdus = DownlinkMACPDU::dissociate(&block).unwrap();
+        let [
+            DownlinkMACPDU::MACResource(first),
+            DownlinkMACPDU::MACResource(second),
+            DownlinkMACPDU::MACResource(null)
+        ] = &pdus[..] else {
+            panic!("expected two PDUs and a Null PDU, got {pdus:?}");
+        };
+
+        assert_eq!(first.length, Length::Octets(7));
+        assert_eq!(first.tm_sdu_bits, Bits::repeat(true, 13));
+        assert_eq!(second.address, Address::SSI { address: 2 });
+        assert_eq!(second.tm_sdu_bits, Bits::repeat(true, 5));
+        assert_eq!(null.address, Address::NullPDU);
+    }
+
+    #[test]
+    fn fills_small_remainder_without_null_pdu() {
+
+        // 43 bits of header and 65 of TM-SDU leave too little space for a Null PDU
+        let mut builder = MACBlockBuilder::new(124);
+        builder.push(pdu(1, 65)).unwrap();
+        assert_eq!(builder.remaining(), 12);
+
+        let block = builder.finish();
+        let pdus = DownlinkMACPDU::dissociate(&block).unwrap();
+        assert!(matches!(pdus[..], [DownlinkMACPDU::MACResource(_)]));
+    }
+}
@@ -4,6 +4,27 @@ use bitvec::prelude::*;
 // Bit field where the MSB is stored first (0th)
 pub type Bits = BitVec<u8, Msb0>;
 
+// Soft-decision bits, as log-likelihood ratios. Positive values favour a 0 and negative values a 1,
+// with the magnitude giving the reliability. A value of 0 is an erasure (no information).
+pub type SoftBits = Vec<f32>;
+
+/// A single soft-decision bit which can be converted to a log-likelihood ratio
+pub trait SoftBit: Copy {
+    fn llr(self) -> f32;
+}
+
+impl SoftBit for f32 {
+    fn llr(self) -> f32 {
+        self
+    }
+}
+
+impl SoftBit for i8 {
+    fn llr(self) -> f32 {
+        self as f32
+    }
+}
+
 // Convenient macro that wraps bitvec! to create new Bits instances
 #[macro_export] macro_rules! new_bits {
     ($($elem:expr),* $(,)?) => {
@@ -64,11 +85,21 @@ pub fn from_bitstr(s: &str) -> Bits {
     bv
 }
 
+/// Convert hard bits into soft bits of unit reliability
+pub fn to_soft_bits(bits: &Bits) -> SoftBits {
+    bits.iter().map(|bit| if *bit { -1.0 } else { 1.0 }).collect()
+}
+
+/// Make a hard decision on each of the soft bits, treating erasures as 0
+pub fn hard_decision(soft_bits: &[f32]) -> Bits {
+    soft_bits.iter().map(|llr| *llr < 0.0).collect()
+}
+
 #[cfg(test)]
 mod tests {
 
     use bitvec::prelude::*;
-    use crate::bits::from_bitstr;
+    use crate::bits::{from_bitstr, hard_decision, to_soft_bits};
 
     #[test]
     fn from_bitstr_behaves_identically_to_bitvec_macro() {
@@ -89,4 +120,12 @@ mod tests {
 
     }
 
+    #[test]
+    fn soft_bits_round_trip() {
+        let bits = from_bitstr("0110 1");
+        let soft = to_soft_bits(&bits);
+        assert_eq!(soft, vec![1.0, -1.0, -1.0, 1.0, -1.0]);
+        assert_eq!(hard_decision(&soft), bits);
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,112 @@
+use crate::bits::Bits;
+use crate::burst::{Build, BurstExtractionError, Extract};
+use crate::burst::partial::tail_bits::tail_bits;
+use crate::burst::partial::training_sequence::training_sequence_extended_bits;
+
+#[derive(Debug)]
+pub struct ControlUplinkBurst {
+    pub ssn1_bits: Bits,
+    pub ssn2_bits: Bits
+}
+
+impl Build for ControlUplinkBurst {
+    /// Builds the control uplink burst (EN 300 392-2 § 9.4.4.2.1)
+    fn build(&self) -> Bits {
+
+        // Validate the lengths of the blocks
+        if self.ssn1_bits.len() != 84 {
+            panic!("ssn1 block length for control uplink burst must be 84 bits got {}", self.ssn1_bits.len())
+        }
+
+        if self.ssn2_bits.len() != 84 {
+            panic!("ssn2 block length for control uplink burst must be 84 bits got {}", self.ssn2_bits.len())
+        }
+
+        // Build the burst
+        let mut burst = Bits::new();
+
+        // Tail bits (t1-t4)
+        burst.extend(&tail_bits()[0..4]);
+
+        // SSN1
+        burst.extend(&self.ssn1_bits);
+
+        // Extended training sequence (x1-x30)
+        burst.extend(&training_sequence_extended_bits()[0..30]);
+
+        // SSN2
+        burst.extend(&self.ssn2_bits);
+
+        // Tail bits (t1-t4)
+        burst.extend(&tail_bits()[0..4]);
+
+        burst
+    }
+}
+
+impl Extract for ControlUplinkBurst {
+
+    /// Validates and extracts the control uplink burst from bits
+    fn extract(burst: Bits) -> Result<ControlUplinkBurst, BurstExtractionError> {
+
+        if burst.len() != 206 {
+            return Err(BurstExtractionError::IncorrectLength {
+                expected: 206,
+                provided: burst.len()
+            })
+        }
+
+        if burst[88..118] != training_sequence_extended_bits() {
+            return Err(BurstExtractionError::InvalidSequence);
+        }
+
+        Ok(ControlUplinkBurst {
+            ssn1_bits: Bits::from_bitslice(&burst[4..88]),
+            ssn2_bits: Bits::from_bitslice(&burst[118..202])
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::Bits;
+
+    #[test]
+    fn empty_burst_is_correct() {
+
+        let burst = ControlUplinkBurst {
+            ssn1_bits: Bits::repeat(false, 84),
+            ssn2_bits: Bits::repeat(false, 84)
+        };
+
+        let burst = burst.build();
+        assert_eq!(burst.len(), 206);
+        assert_eq!(burst[88..118], training_sequence_extended_bits());
+    }
+
+    #[test]
+    fn extracts_built_burst() {
+
+        let mut ssn1_bits = Bits::repeat(false, 84);
+        ssn1_bits.fill_with(|idx| idx % 2 == 0);
+        let ssn2_bits = !ssn1_bits.clone();
+
+        let burst = ControlUplinkBurst {
+            ssn1_bits: ssn1_bits.clone(),
+            ssn2_bits: ssn2_bits.clone()
+        };
+
+        let extracted = ControlUplinkBurst::extract(burst.build()).unwrap();
+        assert_eq!(extracted.ssn1_bits, ssn1_bits);
+        assert_eq!(extracted.ssn2_bits, ssn2_bits);
+    }
+
+    #[test]
+    fn rejects_unknown_training_sequence() {
+        let burst = Bits::repeat(false, 206);
+        assert!(matches!(ControlUplinkBurst::extract(burst), Err(BurstExtractionError::InvalidSequence)));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::bits::Bits;
+use crate::burst::{Build, BurstExtractionError, Extract, SLOT_BITS};
+use crate::burst::partial::guard_period::{guard_period_bits, DISCONTINUOUS_GUARD_START_BITS};
+
+/// Number of bits in a subslot, which the linearisation burst occupies
+const SUBSLOT_BITS: usize = 255;
+
+/// Number of linearisation bits transmitted in the first half of a downlink slot, after ramping up
+pub const DOWNLINK_LINEARISATION_BITS: usize = SUBSLOT_BITS - DISCONTINUOUS_GUARD_START_BITS;
+
+/// Number of bits at the start of the subslot during which the transmitter ramps up
+const LINEARISATION_GUARD_START_BITS: usize = 10;
+
+/// Number of linearisation bits transmitted after ramping up
+pub const LINEARISATION_BITS: usize = 240;
+
+/// Linearisation burst (EN 300 392-2 § 9.4.4.2.2)
+///
+/// Transmitted during a CLCH subslot so that the transmitter can linearise its power amplifier.
+/// The content of the burst is not defined, only its timing, so an arbitrary set of linearisation
+/// bits is carried within the subslot, surrounded by guard periods.
+#[derive(Debug)]
+pub struct LinearisationBurst {
+    pub linearisation_bits: Bits
+}
+
+impl Build for LinearisationBurst {
+    /// Builds the linearisation burst, occupying a whole subslot
+    fn build(&self) -> Bits {
+
+        // Validate the length of the content
+        if self.linearisation_bits.len() != LINEARISATION_BITS {
+            panic!(
+                "linearisation block length for linearisation burst must be {LINEARISATION_BITS} bits got {}",
+                self.linearisation_bits.len()
+            )
+        }
+
+        // Ramp-up guard period
+        let mut burst = guard_period_bits(LINEARISATION_GUARD_START_BITS);
+
+        // Linearisation bits
+        burst.extend(&self.linearisation_bits);
+
+        // Ramp-down guard period for the rest of the subslot
+        burst.extend(guard_period_bits(SUBSLOT_BITS - burst.len()));
+
+        burst
+    }
+}
+
+impl Extract for LinearisationBurst {
+
+    /// Validates and extracts the linearisation burst from a subslot's worth of bits
+    fn extract(burst: Bits) -> Result<LinearisationBurst, BurstExtractionError> {
+
+        if burst.len() != SUBSLOT_BITS {
+            return Err(BurstExtractionError::IncorrectLength {
+                expected: SUBSLOT_BITS,
+                provided: burst.len()
+            })
+        }
+
+        Ok(LinearisationBurst {
+            linearisation_bits: Bits::from_bitslice(
+                &burst[LINEARISATION_GUARD_START_BITS..LINEARISATION_GUARD_START_BITS + LINEARISATION_BITS]
+            )
+        })
+    }
+
+}
+
+/// Downlink linearisation burst, carrying the BLCH
+///
+/// Transmitted by the BS on a carrier which is not continuously transmitted, so that it can
+/// linearise its power amplifier. As on the uplink the content isn't defined: arbitrary bits are
+/// carried in the first half of the slot after ramping up, and nothing in the second half.
+#[derive(Debug)]
+pub struct LinearisationDownlinkBurst {
+    pub linearisation_bits: Bits
+}
+
+impl Build for LinearisationDownlinkBurst {
+    /// Builds the downlink linearisation burst, including the guard periods so that the result
+    /// occupies a whole slot
+    fn build(&self) -> Bits {
+
+        // Validate the length of the content
+        if self.linearisation_bits.len() != DOWNLINK_LINEARISATION_BITS {
+            panic!(
+                "linearisation block length for downlink linearisation burst must be {DOWNLINK_LINEARISATION_BITS} bits got {}",
+                self.linearisation_bits.len()
+            )
+        }
+
+        // Ramp-up guard period
+        let mut burst = guard_period_bits(DISCONTINUOUS_GUARD_START_BITS);
+
+        // Linearisation bits, up to the end of the first half slot
+        burst.extend(&self.linearisation_bits);
+
+        // Nothing is transmitted in the second half slot
+        burst.extend(guard_period_bits(SLOT_BITS - burst.len()));
+
+        burst
+    }
+}
+
+impl Extract for LinearisationDownlinkBurst {
+
+    /// Validates and extracts the downlink linearisation burst from a slot's worth of bits
+    fn extract(burst: Bits) -> Result<LinearisationDownlinkBurst, BurstExtractionError> {
+
+        if burst.len() != SLOT_BITS {
+            return Err(BurstExtractionError::IncorrectLength {
+                expected: SLOT_BITS,
+                provided: burst.len()
+            })
+        }
+
+        Ok(LinearisationDownlinkBurst {
+            linearisation_bits: Bits::from_bitslice(&burst[DISCONTINUOUS_GUARD_START_BITS..SUBSLOT_BITS])
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::Bits;
+
+    #[test]
+    fn extracts_built_burst() {
+
+        let mut linearisation_bits = Bits::repeat(false, LINEARISATION_BITS);
+        linearisation_bits.fill_with(|idx| idx % 3 == 0);
+
+        let burst = LinearisationBurst {
+            linearisation_bits: linearisation_bits.clone()
+        };
+
+        let burst_bits = burst.build();
+        assert_eq!(burst_bits.len(), SUBSLOT_BITS);
+        assert!(burst_bits[..LINEARISATION_GUARD_START_BITS].not_any());
+
+        let extracted = LinearisationBurst::extract(burst_bits).unwrap();
+        assert_eq!(extracted.linearisation_bits, linearisation_bits);
+    }
+
+    #[test]
+    fn extracts_built_downlink_burst() {
+
+        let mut linearisation_bits = Bits::repeat(false, DOWNLINK_LINEARISATION_BITS);
+        linearisation_bits.fill_with(|idx| idx % 3 == 0);
+
+        let burst = LinearisationDownlinkBurst {
+            linearisation_bits: linearisation_bits.clone()
+        };
+
+        let burst_bits = burst.build();
+        assert_eq!(burst_bits.len(), SLOT_BITS);
+        assert!(burst_bits[..DISCONTINUOUS_GUARD_START_BITS].not_any());
+        assert!(burst_bits[SUBSLOT_BITS..].not_any());
+
+        let extracted = LinearisationDownlinkBurst::extract(burst_bits).unwrap();
+        assert_eq!(extracted.linearisation_bits, linearisation_bits);
+    }
+}
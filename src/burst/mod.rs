@@ -1,12 +1,26 @@
-mod partial;
+pub(crate) mod partial;
 mod normal_cont_dl_burst;
 mod sync_cont_dl_burst;
+mod normal_discont_dl_burst;
+mod sync_discont_dl_burst;
+mod normal_ul_burst;
+mod control_ul_burst;
+mod linearisation_burst;
 mod synchroniser;
 
 pub use sync_cont_dl_burst::SyncContDownlinkBurst;
 pub use normal_cont_dl_burst::NormalContDownlinkBurst;
+pub use sync_discont_dl_burst::SyncDiscontDownlinkBurst;
+pub use normal_discont_dl_burst::NormalDiscontDownlinkBurst;
+pub use normal_ul_burst::NormalUplinkBurst;
+pub use control_ul_burst::ControlUplinkBurst;
+pub use linearisation_burst::{LinearisationBurst, LinearisationDownlinkBurst, LINEARISATION_BITS};
+pub use synchroniser::Synchroniser;
 use crate::bits::Bits;
 
+/// Number of bits in a downlink slot
+pub const SLOT_BITS: usize = 510;
+
 /// A burst that is extractable from Bits
 pub trait Extract: Sized {
     fn extract(burst: Bits) -> Result<Self, BurstExtractionError>;
@@ -30,4 +44,48 @@ pub enum BurstExtractionError {
 pub enum DownlinkBurst {
     Sync(SyncContDownlinkBurst),
     Normal(NormalContDownlinkBurst),
+    SyncDiscontinuous(SyncDiscontDownlinkBurst),
+    NormalDiscontinuous(NormalDiscontDownlinkBurst),
+    Linearisation(LinearisationDownlinkBurst),
+}
+
+impl DownlinkBurst {
+    /// Convert into the equivalent discontinuous burst, for carriers which are not continuously
+    /// transmitted
+    pub fn into_discontinuous(self) -> Self {
+        match self {
+            DownlinkBurst::Sync(burst) => DownlinkBurst::SyncDiscontinuous(burst.into()),
+            DownlinkBurst::Normal(burst) => DownlinkBurst::NormalDiscontinuous(burst.into()),
+            discontinuous => discontinuous
+        }
+    }
+}
+
+impl Build for DownlinkBurst {
+    fn build(&self) -> Bits {
+        match self {
+            DownlinkBurst::Sync(burst) => burst.build(),
+            DownlinkBurst::Normal(burst) => burst.build(),
+            DownlinkBurst::SyncDiscontinuous(burst) => burst.build(),
+            DownlinkBurst::NormalDiscontinuous(burst) => burst.build(),
+            DownlinkBurst::Linearisation(burst) => burst.build(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum UplinkBurst {
+    Control(ControlUplinkBurst),
+    Normal(NormalUplinkBurst),
+    Linearisation(LinearisationBurst),
+}
+
+impl Build for UplinkBurst {
+    fn build(&self) -> Bits {
+        match self {
+            UplinkBurst::Control(burst) => burst.build(),
+            UplinkBurst::Normal(burst) => burst.build(),
+            UplinkBurst::Linearisation(burst) => burst.build(),
+        }
+    }
 }
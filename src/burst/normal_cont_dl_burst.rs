@@ -112,8 +112,8 @@ impl Extract for NormalContDownlinkBurst {
         // Combine the broadcast bits into a single 30-bit field
         let bb_bits = {
             let mut bits = Bits::new();
-            bits.extend(&burst[0..10]);
-            bits.extend(&burst[20..30]);
+            bits.extend(&burst[230..244]);
+            bits.extend(&burst[266..282]);
             bits
         };
 
@@ -0,0 +1,219 @@
+use phase_adjustment::phase_adjustment_bits;
+use crate::bits::Bits;
+use crate::burst::{Build, BurstExtractionError, Extract, NormalContDownlinkBurst};
+use crate::burst::partial::guard_period::{
+    guard_period_bits,
+    DISCONTINUOUS_GUARD_END_BITS,
+    DISCONTINUOUS_GUARD_START_BITS
+};
+use crate::burst::partial::tail_bits::tail_bits;
+use crate::burst::partial::training_sequence::{
+    training_sequence_normal_1_bits,
+    training_sequence_normal_2_bits
+};
+use crate::burst::partial::phase_adjustment;
+use crate::burst::partial::phase_adjustment::{
+    extract_sn_range_bits,
+    PHASE_ADJUSTMENT_SYMBOL_RANGE_HG,
+    PHASE_ADJUSTMENT_SYMBOL_RANGE_HH
+};
+
+/// Normal discontinuous downlink burst, as used on carriers which are not continuously
+/// transmitted (i.e. carrier or MCCH sharing)
+#[derive(Debug)]
+pub struct NormalDiscontDownlinkBurst {
+    pub bkn1_bits: Bits,
+    pub bkn2_bits: Bits,
+    pub bb_bits: Bits,
+    pub slot_flag: bool
+}
+
+impl From<NormalContDownlinkBurst> for NormalDiscontDownlinkBurst {
+    fn from(burst: NormalContDownlinkBurst) -> Self {
+        NormalDiscontDownlinkBurst {
+            bkn1_bits: burst.bkn1_bits,
+            bkn2_bits: burst.bkn2_bits,
+            bb_bits: burst.bb_bits,
+            slot_flag: burst.slot_flag
+        }
+    }
+}
+
+impl From<&NormalDiscontDownlinkBurst> for NormalContDownlinkBurst {
+    /// The same blocks as a continuous burst, for decoding by the receiver
+    fn from(burst: &NormalDiscontDownlinkBurst) -> Self {
+        NormalContDownlinkBurst {
+            bkn1_bits: burst.bkn1_bits.clone(),
+            bkn2_bits: burst.bkn2_bits.clone(),
+            bb_bits: burst.bb_bits.clone(),
+            slot_flag: burst.slot_flag
+        }
+    }
+}
+
+impl Build for NormalDiscontDownlinkBurst {
+    /// Builds the normal discontinuous downlink burst, including the guard periods either side
+    /// so that the result occupies a whole slot
+    fn build(&self) -> Bits {
+
+        // Validate the lengths of the blocks
+        if self.bkn1_bits.len() != 216 {
+            panic!("bkn1 block length for normal discont downlink burst must be 216 bits got {}", self.bkn1_bits.len())
+        }
+
+        if self.bkn2_bits.len() != 216 {
+            panic!("bkn2 block length for normal discont downlink burst must be 216 bits got {}", self.bkn2_bits.len())
+        }
+
+        if self.bb_bits.len() != 30 {
+            panic!("broadcast block length for normal discont downlink burst must be 30 bits got {}", self.bb_bits.len())
+        }
+
+        // Build the burst
+        let mut burst = Bits::new();
+
+        // Tail bits (t1-t2)
+        burst.extend(&tail_bits()[0..2]);
+
+        // Placeholder bits for the first phase adjustment bits
+        let pa_a_ref = burst.len();
+        burst.extend([false; 2]);
+
+        // BKN1
+        burst.extend(&self.bkn1_bits);
+
+        // BB (14 bits)
+        burst.extend(&self.bb_bits[0..14]);
+
+        // Training sequence 2 if the slot flag is present, otherwise training sequence 1
+        if self.slot_flag {
+            // p1-p22
+            burst.extend(&training_sequence_normal_2_bits()[0..22]);
+        } else {
+            // n1-n22
+            burst.extend(&training_sequence_normal_1_bits()[0..22]);
+        }
+
+        // BB (16 bits)
+        burst.extend(&self.bb_bits[14..30]);
+
+        // BKN2
+        burst.extend(&self.bkn2_bits);
+
+        // Placeholder bits for the second phase adjustment bits
+        let pa_b_ref = burst.len();
+        burst.extend([false; 2]);
+
+        // Tail bits (t3-t4)
+        burst.extend(&tail_bits()[2..4]);
+
+        // Calculate the phase adjustment fields
+        // The first PA bits are defined by "HG"
+        let pa_a_bits = phase_adjustment_bits(
+            &extract_sn_range_bits(&burst, PHASE_ADJUSTMENT_SYMBOL_RANGE_HG.0, PHASE_ADJUSTMENT_SYMBOL_RANGE_HG.1)
+        );
+
+        // The second PA bits are defined by "HH"
+        let pa_b_bits = phase_adjustment_bits(
+            &extract_sn_range_bits(&burst, PHASE_ADJUSTMENT_SYMBOL_RANGE_HH.0, PHASE_ADJUSTMENT_SYMBOL_RANGE_HH.1)
+        );
+
+        // Insert the PA bits into the structure
+        burst.splice(pa_a_ref..pa_a_ref + 2, pa_a_bits);
+        burst.splice(pa_b_ref..pa_b_ref + 2, pa_b_bits);
+
+        // Surround with the guard periods
+        let mut slot = guard_period_bits(DISCONTINUOUS_GUARD_START_BITS);
+        slot.extend(burst);
+        slot.extend(guard_period_bits(DISCONTINUOUS_GUARD_END_BITS));
+
+        slot
+    }
+}
+
+impl Extract for NormalDiscontDownlinkBurst {
+
+    /// Validates and extracts the normal discontinuous downlink burst from a slot's worth of bits
+    fn extract(burst: Bits) -> Result<NormalDiscontDownlinkBurst, BurstExtractionError> {
+
+        if burst.len() != 510 {
+            return Err(BurstExtractionError::IncorrectLength {
+                expected: 510,
+                provided: burst.len()
+            })
+        }
+
+        // Combine the broadcast bits into a single 30-bit field
+        let bb_bits = {
+            let mut bits = Bits::new();
+            bits.extend(&burst[230..244]);
+            bits.extend(&burst[266..282]);
+            bits
+        };
+
+        // Determine the slot flag value
+        let slot_flag = if burst[244..266] == training_sequence_normal_1_bits() {
+            false
+        } else if burst[244..266] == training_sequence_normal_2_bits() {
+            true
+        } else {
+            return Err(BurstExtractionError::InvalidSequence);
+        };
+
+        Ok(NormalDiscontDownlinkBurst {
+            bkn1_bits: Bits::from_bitslice(&burst[14..230]),
+            bb_bits,
+            bkn2_bits: Bits::from_bitslice(&burst[282..498]),
+            slot_flag
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::Bits;
+
+    #[test]
+    fn empty_burst_is_correct() {
+
+        let burst = NormalDiscontDownlinkBurst {
+            bkn1_bits: Bits::repeat(false, 216),
+            bkn2_bits: Bits::repeat(false, 216),
+            bb_bits: Bits::repeat(false, 30),
+            slot_flag: false,
+        };
+
+        let burst = burst.build();
+        assert_eq!(burst.len(), 510);
+
+        // Nothing is sent during the guard periods
+        assert!(burst[0..DISCONTINUOUS_GUARD_START_BITS].not_any());
+        assert!(burst[510 - DISCONTINUOUS_GUARD_END_BITS..].not_any());
+    }
+
+    #[test]
+    fn extracts_built_burst() {
+
+        let mut bkn1_bits = Bits::repeat(false, 216);
+        bkn1_bits.fill_with(|idx| idx % 3 == 0);
+        let bkn2_bits = !bkn1_bits.clone();
+        let mut bb_bits = Bits::repeat(false, 30);
+        bb_bits.fill_with(|idx| idx % 4 == 0);
+
+        let burst = NormalDiscontDownlinkBurst {
+            bkn1_bits: bkn1_bits.clone(),
+            bkn2_bits: bkn2_bits.clone(),
+            bb_bits: bb_bits.clone(),
+            slot_flag: true
+        };
+
+        let extracted = NormalDiscontDownlinkBurst::extract(burst.build()).unwrap();
+        assert_eq!(extracted.bkn1_bits, bkn1_bits);
+        assert_eq!(extracted.bkn2_bits, bkn2_bits);
+        assert_eq!(extracted.bb_bits, bb_bits);
+        assert!(extracted.slot_flag);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::bits::Bits;
+use crate::burst::{Build, BurstExtractionError, Extract};
+use crate::burst::partial::tail_bits::tail_bits;
+use crate::burst::partial::training_sequence::{
+    training_sequence_normal_1_bits,
+    training_sequence_normal_2_bits
+};
+
+#[derive(Debug)]
+pub struct NormalUplinkBurst {
+    pub bkn1_bits: Bits,
+    pub bkn2_bits: Bits,
+    pub slot_flag: bool
+}
+
+impl Build for NormalUplinkBurst {
+    /// Builds the normal uplink burst (EN 300 392-2 § 9.4.4.2.3)
+    fn build(&self) -> Bits {
+
+        // Validate the lengths of the blocks
+        if self.bkn1_bits.len() != 216 {
+            panic!("bkn1 block length for normal uplink burst must be 216 bits got {}", self.bkn1_bits.len())
+        }
+
+        if self.bkn2_bits.len() != 216 {
+            panic!("bkn2 block length for normal uplink burst must be 216 bits got {}", self.bkn2_bits.len())
+        }
+
+        // Build the burst
+        let mut burst = Bits::new();
+
+        // Tail bits (t1-t4)
+        burst.extend(&tail_bits()[0..4]);
+
+        // BKN1
+        burst.extend(&self.bkn1_bits);
+
+        // Training sequence 2 if the slot flag is present, otherwise training sequence 1
+        if self.slot_flag {
+            // p1-p22
+            burst.extend(&training_sequence_normal_2_bits()[0..22]);
+        } else {
+            // n1-n22
+            burst.extend(&training_sequence_normal_1_bits()[0..22]);
+        }
+
+        // BKN2
+        burst.extend(&self.bkn2_bits);
+
+        // Tail bits (t1-t4)
+        burst.extend(&tail_bits()[0..4]);
+
+        burst
+    }
+}
+
+impl Extract for NormalUplinkBurst {
+
+    /// Validates and extracts the normal uplink burst from bits
+    fn extract(burst: Bits) -> Result<NormalUplinkBurst, BurstExtractionError> {
+
+        if burst.len() != 462 {
+            return Err(BurstExtractionError::IncorrectLength {
+                expected: 462,
+                provided: burst.len()
+            })
+        }
+
+        // Determine the slot flag value
+        let slot_flag = if burst[220..242] == training_sequence_normal_1_bits() {
+            false
+        } else if burst[220..242] == training_sequence_normal_2_bits() {
+            true
+        } else {
+            return Err(BurstExtractionError::InvalidSequence);
+        };
+
+        Ok(NormalUplinkBurst {
+            bkn1_bits: Bits::from_bitslice(&burst[4..220]),
+            bkn2_bits: Bits::from_bitslice(&burst[242..458]),
+            slot_flag
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::Bits;
+
+    #[test]
+    fn empty_burst_is_correct() {
+
+        let burst = NormalUplinkBurst {
+            bkn1_bits: Bits::repeat(false, 216),
+            bkn2_bits: Bits::repeat(false, 216),
+            slot_flag: false,
+        };
+
+        let burst = burst.build();
+        assert_eq!(burst.len(), 462);
+        assert_eq!(burst[0..4], tail_bits());
+        assert_eq!(burst[458..462], tail_bits());
+    }
+
+    #[test]
+    fn extracts_built_burst() {
+
+        let mut bkn1_bits = Bits::repeat(false, 216);
+        bkn1_bits.fill_with(|idx| idx % 3 == 0);
+        let bkn2_bits = !bkn1_bits.clone();
+
+        for slot_flag in [false, true] {
+
+            let burst = NormalUplinkBurst {
+                bkn1_bits: bkn1_bits.clone(),
+                bkn2_bits: bkn2_bits.clone(),
+                slot_flag
+            };
+
+            let extracted = NormalUplinkBurst::extract(burst.build()).unwrap();
+            assert_eq!(extracted.bkn1_bits, bkn1_bits);
+            assert_eq!(extracted.bkn2_bits, bkn2_bits);
+            assert_eq!(extracted.slot_flag, slot_flag);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_training_sequence() {
+        let burst = Bits::repeat(false, 462);
+        assert!(matches!(NormalUplinkBurst::extract(burst), Err(BurstExtractionError::InvalidSequence)));
+    }
+}
@@ -0,0 +1,15 @@
+use crate::bits::Bits;
+
+/// Number of bits at the start of a discontinuous downlink slot during which the transmitter
+/// ramps up (5 symbols)
+pub const DISCONTINUOUS_GUARD_START_BITS: usize = 10;
+
+/// Number of bits at the end of a discontinuous downlink slot during which the transmitter
+/// ramps down (4 symbols)
+pub const DISCONTINUOUS_GUARD_END_BITS: usize = 8;
+
+/// Generates the bits sent during a guard period, while the transmitter is ramping.
+/// Nothing is modulated during this time, so these are simply all-0.
+pub fn guard_period_bits(length: usize) -> Bits {
+    Bits::repeat(false, length)
+}
\ No newline at end of file
@@ -1,4 +1,5 @@
-mod tail_bits;
+pub mod tail_bits;
 pub mod training_sequence;
 pub mod frequency_correction;
-pub mod phase_adjustment;
\ No newline at end of file
+pub mod phase_adjustment;
+pub mod guard_period;
\ No newline at end of file
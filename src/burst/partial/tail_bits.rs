@@ -1,8 +1,7 @@
-use bitvec::prelude::*;
 use crate::bits::Bits;
 use crate::bits::from_bitstr;
 
-/// Generates the tail bits
-fn tail_bits() -> Bits {
+/// Generates the tail bits t1-t4 (EN 300 392-2 9.4.4.3.5)
+pub(crate) fn tail_bits() -> Bits {
     from_bitstr("1, 1, 0, 0")
 }
\ No newline at end of file
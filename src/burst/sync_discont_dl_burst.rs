@@ -0,0 +1,189 @@
+use phase_adjustment::phase_adjustment_bits;
+use crate::bits::Bits;
+use crate::burst::{Build, BurstExtractionError, Extract, SyncContDownlinkBurst};
+use crate::burst::partial::guard_period::{
+    guard_period_bits,
+    DISCONTINUOUS_GUARD_END_BITS,
+    DISCONTINUOUS_GUARD_START_BITS
+};
+use crate::burst::partial::tail_bits::tail_bits;
+use crate::burst::partial::training_sequence::training_sequence_sync_bits;
+use crate::burst::partial::frequency_correction::{
+    frequency_correction_bits
+};
+use crate::burst::partial::phase_adjustment;
+use crate::burst::partial::phase_adjustment::{
+    extract_sn_range_bits,
+    PHASE_ADJUSTMENT_SYMBOL_RANGE_HI,
+    PHASE_ADJUSTMENT_SYMBOL_RANGE_HJ
+};
+
+/// Synchronisation discontinuous downlink burst, as used on carriers which are not continuously
+/// transmitted (i.e. carrier or MCCH sharing)
+#[derive(Debug)]
+pub struct SyncDiscontDownlinkBurst {
+    pub(crate) sb1_bits: Bits,
+    pub(crate) sb2_bits: Bits,
+    pub(crate) bb_bits: Bits
+}
+
+impl From<SyncContDownlinkBurst> for SyncDiscontDownlinkBurst {
+    fn from(burst: SyncContDownlinkBurst) -> Self {
+        SyncDiscontDownlinkBurst {
+            sb1_bits: burst.sb1_bits,
+            sb2_bits: burst.sb2_bits,
+            bb_bits: burst.bb_bits
+        }
+    }
+}
+
+impl From<&SyncDiscontDownlinkBurst> for SyncContDownlinkBurst {
+    /// The same blocks as a continuous burst, for decoding by the receiver
+    fn from(burst: &SyncDiscontDownlinkBurst) -> Self {
+        SyncContDownlinkBurst {
+            sb1_bits: burst.sb1_bits.clone(),
+            sb2_bits: burst.sb2_bits.clone(),
+            bb_bits: burst.bb_bits.clone()
+        }
+    }
+}
+
+impl Build for SyncDiscontDownlinkBurst {
+    /// Builds the synchronisation discontinuous downlink burst, including the guard periods
+    /// either side so that the result occupies a whole slot
+    fn build(&self) -> Bits {
+
+        // Validate the lengths of the blocks
+        if self.sb1_bits.len() != 120 {
+            panic!("sb1 block length for sync discont downlink burst must be 120 bits got {}", self.sb1_bits.len())
+        }
+
+        if self.sb2_bits.len() != 216 {
+            panic!("sb2 block length for sync discont downlink burst must be 216 bits got {}", self.sb2_bits.len())
+        }
+
+        if self.bb_bits.len() != 30 {
+            panic!("broadcast block length for sync discont downlink burst must be 30 bits got {}", self.bb_bits.len())
+        }
+
+        // Build the burst
+        let mut burst_bits = Bits::new();
+
+        // Tail bits (t1-t2)
+        burst_bits.extend(&tail_bits()[0..2]);
+
+        // Placeholder bits phase adjustment A
+        let pa_a_ref = burst_bits.len();
+        burst_bits.extend([false; 2]);
+
+        // Frequency correction bits (f1-f80)
+        burst_bits.extend(&frequency_correction_bits()[0..80]);
+
+        // SB1
+        burst_bits.extend(&self.sb1_bits);
+
+        // Synchronisation training sequence (y1-38)
+        burst_bits.extend(&training_sequence_sync_bits()[0..38]);
+
+        // BB (30 bits)
+        burst_bits.extend(&self.bb_bits[0..30]);
+
+        // SB2
+        burst_bits.extend(&self.sb2_bits);
+
+        // Placeholder bits phase adjustment B
+        let pa_b_ref = burst_bits.len();
+        burst_bits.extend([false; 2]);
+
+        // Tail bits (t3-t4)
+        burst_bits.extend(&tail_bits()[2..4]);
+
+        // Calculate the phase adjustment fields
+        // Phase Adjustment A bits are defined by "HI"
+        let pa_a_bits = phase_adjustment_bits(
+            &extract_sn_range_bits(&burst_bits, PHASE_ADJUSTMENT_SYMBOL_RANGE_HI.0, PHASE_ADJUSTMENT_SYMBOL_RANGE_HI.1)
+        );
+
+        // Phase Adjustment B bits are defined by "HJ"
+        let pa_b_bits = phase_adjustment_bits(
+            &extract_sn_range_bits(&burst_bits, PHASE_ADJUSTMENT_SYMBOL_RANGE_HJ.0, PHASE_ADJUSTMENT_SYMBOL_RANGE_HJ.1)
+        );
+
+        // Insert the A and B phase adjustment bits into the structure
+        burst_bits.splice(pa_a_ref..pa_a_ref + 2, pa_a_bits);
+        burst_bits.splice(pa_b_ref..pa_b_ref + 2, pa_b_bits);
+
+        // Surround with the guard periods
+        let mut slot = guard_period_bits(DISCONTINUOUS_GUARD_START_BITS);
+        slot.extend(burst_bits);
+        slot.extend(guard_period_bits(DISCONTINUOUS_GUARD_END_BITS));
+
+        slot
+    }
+}
+
+impl Extract for SyncDiscontDownlinkBurst {
+
+    /// Validate and extract the synchronisation discontinuous downlink burst from a slot's worth
+    /// of bits
+    fn extract(burst: Bits) -> Result<SyncDiscontDownlinkBurst, BurstExtractionError> {
+
+        if burst.len() != 510 {
+            return Err(BurstExtractionError::IncorrectLength {
+                expected: 510,
+                provided: burst.len()
+            })
+        }
+
+        Ok(SyncDiscontDownlinkBurst {
+            sb1_bits: Bits::from_bitslice(&burst[94..214]),
+            bb_bits: Bits::from_bitslice(&burst[252..282]),
+            sb2_bits: Bits::from_bitslice(&burst[282..498])
+        })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::Bits;
+
+    #[test]
+    fn empty_burst_is_correct() {
+
+        let burst = SyncDiscontDownlinkBurst {
+            sb1_bits: Bits::repeat(false, 120),
+            sb2_bits: Bits::repeat(false, 216),
+            bb_bits: Bits::repeat(false, 30)
+        };
+
+        let burst_bits = burst.build();
+        assert_eq!(burst_bits.len(), 510);
+
+        // The sync training sequence sits at the same position as in the continuous burst
+        assert_eq!(burst_bits[214..252], training_sequence_sync_bits());
+    }
+
+    #[test]
+    fn extracts_built_burst() {
+
+        let mut sb1_bits = Bits::repeat(false, 120);
+        sb1_bits.fill_with(|idx| idx % 3 == 0);
+        let mut sb2_bits = Bits::repeat(false, 216);
+        sb2_bits.fill_with(|idx| idx % 5 == 0);
+        let bb_bits = Bits::repeat(true, 30);
+
+        let burst = SyncDiscontDownlinkBurst {
+            sb1_bits: sb1_bits.clone(),
+            sb2_bits: sb2_bits.clone(),
+            bb_bits: bb_bits.clone()
+        };
+
+        let extracted = SyncDiscontDownlinkBurst::extract(burst.build()).unwrap();
+        assert_eq!(extracted.sb1_bits, sb1_bits);
+        assert_eq!(extracted.sb2_bits, sb2_bits);
+        assert_eq!(extracted.bb_bits, bb_bits);
+    }
+}
@@ -1,7 +1,10 @@
-use bitvec::vec::BitVec;
+use bitvec::order::Msb0;
+use bitvec::slice::BitSlice;
 use crate::bits::Bits;
 
-struct BitRing {
+/// Fixed-capacity ring of bits.
+/// Once full, pushing a new bit ages-out the oldest.
+pub(crate) struct BitRing {
     buffer: Bits,
     head: usize,
     length: usize,
@@ -11,14 +14,104 @@ impl BitRing {
 
     pub fn new(capacity: usize) -> Self {
         BitRing {
-            buffer: BitVec::with_capacity(capacity),
+            buffer: Bits::repeat(false, capacity),
             head: 0,
             length: 0,
         }
     }
 
+    /// Maximum number of bits that the ring can hold
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of bits currently held
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Whether the ring is at capacity
+    pub fn is_full(&self) -> bool {
+        self.length == self.capacity()
+    }
+
+    /// Push a single bit onto the end of the ring
+    pub fn push_bit(&mut self, bit: bool) {
+
+        let tail = (self.head + self.length) % self.capacity();
+        self.buffer.set(tail, bit);
+
+        // Age-out the oldest bit if already full
+        if self.is_full() {
+            self.head = (self.head + 1) % self.capacity();
+        } else {
+            self.length += 1;
+        }
+    }
+
+    /// Push a sequence of bits onto the end of the ring
     pub fn push(&mut self, bits: Bits) {
+        for bit in bits.iter() {
+            self.push_bit(*bit);
+        }
+    }
 
+    /// Get the bit at the specified index, where 0 is the oldest bit held
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.length, "index {index} out of range for ring holding {} bits", self.length);
+        self.buffer[(self.head + index) % self.capacity()]
     }
 
-}
\ No newline at end of file
+    /// Count the bits differing from `expected` starting at `start`, where 0 is the oldest bit
+    /// held. Stops counting once more than `max_errors` are found.
+    pub fn count_errors(&self, start: usize, expected: &BitSlice<u8, Msb0>, max_errors: usize) -> usize {
+        let mut errors = 0;
+        for (index, bit) in expected.iter().enumerate() {
+            if self.get(start + index) != *bit {
+                errors += 1;
+                if errors > max_errors {
+                    break;
+                }
+            }
+        }
+        errors
+    }
+
+    /// Copy out a range of bits, where 0 is the oldest bit held
+    pub fn range(&self, start: usize, length: usize) -> Bits {
+        (start..start + length).map(|index| self.get(index)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn ages_out_oldest_bits() {
+
+        let mut ring = BitRing::new(4);
+        ring.push(from_bitstr("110"));
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.range(0, 3), from_bitstr("110"));
+
+        // Wraps, dropping the first two bits
+        ring.push(from_bitstr("001"));
+        assert!(ring.is_full());
+        assert_eq!(ring.range(0, 4), from_bitstr("0001"));
+    }
+
+    #[test]
+    fn counts_errors_across_wrap() {
+
+        let mut ring = BitRing::new(4);
+        ring.push(from_bitstr("110101"));
+        assert_eq!(ring.count_errors(1, &from_bitstr("101"), 3), 0);
+        assert_eq!(ring.count_errors(0, &from_bitstr("1010"), 4), 4);
+
+        // Stops once the tolerance is exceeded
+        assert_eq!(ring.count_errors(0, &from_bitstr("1010"), 1), 2);
+    }
+}
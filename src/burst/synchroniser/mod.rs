@@ -1,2 +1,4 @@
 mod bit_ring;
-mod synchroniser;
\ No newline at end of file
+mod synchroniser;
+
+pub use synchroniser::Synchroniser;
@@ -1,58 +1,282 @@
 use crate::bits::Bits;
+use crate::burst::{DownlinkBurst, Extract, NormalContDownlinkBurst, SyncContDownlinkBurst};
+use crate::burst::partial::training_sequence::{
+    training_sequence_normal_1_bits,
+    training_sequence_normal_2_bits,
+    training_sequence_sync_bits
+};
+use super::bit_ring::BitRing;
 
-enum State {
+/// Length of a downlink burst (one slot)
+const BURST_BITS: usize = 510;
 
-    // Haven't seen any training sequence yet
+/// Position of the synchronisation training sequence within the SB
+const SYNC_TRAINING_SEQUENCE_POSITION: usize = 214;
+
+/// Position of the normal training sequence within the NDB
+const NORMAL_TRAINING_SEQUENCE_POSITION: usize = 244;
+
+/// Number of consecutive bursts that may be missed before lock is lost
+const MAX_MISSED_BURSTS: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum State {
+
+    // Haven't seen any training sequence yet, or have missed too many bursts since
     Unlocked,
 
-    // Have seen a training sequence, but not currently receiving a burst
+    // Have seen a training sequence, so know where the next burst starts, but haven't received
+    // any of it yet
     Locked,
 
-    // Have seen a training sequence and currently receiving a burst
+    // Have received the start of the expected burst, and are waiting for the rest of it
     LockedAndBurstStarted,
 }
 
-/// Which type of bursts the synchroniser should search for
-enum SyncMode {
-    Uplink,
-    Downlink
+/// A burst found by the synchroniser
+#[derive(Debug)]
+pub struct SynchronisedBurst {
+    pub burst: DownlinkBurst,
+
+    /// Position of the first bit of the burst's slot within the stream fed to the synchroniser
+    pub offset: usize
 }
 
-struct Synchroniser {
+/// TETRA downlink burst synchroniser
+///
+/// Bits are fed in as they're received and pushed onto a rolling buffer of one slot's length.
+/// Whilst unlocked, every position is searched for a sync or normal training sequence, allowing
+/// for a configurable number of bit errors. The training sequences are compared in place on the
+/// buffer, so a burst is only copied out once one is found. Then the slot timing is known, so
+/// each subsequent slot is extracted at the expected position until too many are missed.
+pub struct Synchroniser {
     state: State,
-    mode: SyncMode,
-    buffer: Bits
+    buffer: BitRing,
+    max_errors: usize,
+    position: usize,
+    burst_start: usize,
+    missed_bursts: usize,
+    sync_training_sequence: Bits,
+    normal_training_sequences: [Bits; 2]
 }
 
-/// TETRA burst synchroniser
-///
-/// A chunk of bits is passed in.
-/// If we don't have enough space in the rolling buffer, age-out enough bits to make space.
-/// Copy the bits into the end of the rolling buffer.
 impl Synchroniser {
 
-    pub fn new() -> Synchroniser {
+    /// Create a synchroniser which accepts training sequences with up to `max_errors` bit errors
+    pub fn new(max_errors: usize) -> Synchroniser {
         Synchroniser {
             state: State::Unlocked,
-            mode: SyncMode::Uplink,
-            buffer: Bits::new()
+            buffer: BitRing::new(BURST_BITS),
+            max_errors,
+            position: 0,
+            burst_start: 0,
+            missed_bursts: 0,
+            sync_training_sequence: training_sequence_sync_bits(),
+            normal_training_sequences: [training_sequence_normal_1_bits(), training_sequence_normal_2_bits()]
         }
     }
 
-    fn append_bits_to_buffer(&mut self, bits: Bits) {
+    /// The current state of the synchroniser
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Feed bits into the synchroniser, returning any bursts that were completed by them
+    pub fn feed(&mut self, bits: Bits) -> Vec<SynchronisedBurst> {
+
+        let mut bursts = Vec::new();
+
+        for bit in bits.iter() {
+
+            self.buffer.push_bit(*bit);
+            self.position += 1;
+
+            match self.state {
+
+                // Search for a burst ending at this position
+                State::Unlocked => {
+                    if !self.buffer.is_full() {
+                        continue;
+                    }
+
+                    if let Some(burst) = self.extract_burst() {
+                        log::info!("synchroniser locked at {}", self.position - BURST_BITS);
+                        bursts.push(SynchronisedBurst {
+                            burst,
+                            offset: self.position - BURST_BITS
+                        });
+                        self.lock();
+                    }
+                },
+
+                // This bit is the first of the expected burst
+                State::Locked => {
+                    self.state = State::LockedAndBurstStarted;
+                },
 
-        // Check if there's enough space
-        let space_left = self.buffer.capacity() - self.buffer.len();
-        if space_left < bits.len() {
-            // Shift out enough bits to fit the new ones in
-            let space_needed = bits.len() - space_left;
-            self.buffer.resize(space_needed, false);
+                State::LockedAndBurstStarted => {}
+            }
+
+            // Extract the burst once it's complete
+            if self.state == State::LockedAndBurstStarted && self.position - self.burst_start == BURST_BITS {
+                match self.extract_burst() {
+                    Some(burst) => {
+                        bursts.push(SynchronisedBurst {
+                            burst,
+                            offset: self.burst_start
+                        });
+                        self.lock();
+                    },
+                    None => self.miss()
+                }
+            }
         }
 
+        bursts
+    }
+
+    /// A burst has just been completed, so the next one starts with the next bit
+    fn lock(&mut self) {
+        self.state = State::Locked;
+        self.burst_start = self.position;
+        self.missed_bursts = 0;
+    }
+
+    /// No burst was found at the expected position
+    fn miss(&mut self) {
+
+        self.missed_bursts += 1;
+
+        if self.missed_bursts > MAX_MISSED_BURSTS {
+            log::info!("synchroniser lost lock at {}", self.position);
+            self.state = State::Unlocked;
+            self.missed_bursts = 0;
+        } else {
+            self.state = State::Locked;
+            self.burst_start = self.position;
+        }
     }
 
-    pub fn feed(&self, bits: Bits) {
+    /// Attempt to extract a burst from the buffer, based on the training sequence it contains
+    fn extract_burst(&self) -> Option<DownlinkBurst> {
 
+        // The synchronisation training sequence is the longest, so try that first
+        if let Some(burst) = self.copy_burst(SYNC_TRAINING_SEQUENCE_POSITION, &self.sync_training_sequence) {
+            return SyncContDownlinkBurst::extract(burst).ok().map(DownlinkBurst::Sync);
+        }
+
+        for training_sequence in &self.normal_training_sequences {
+            if let Some(burst) = self.copy_burst(NORMAL_TRAINING_SEQUENCE_POSITION, training_sequence) {
+                return NormalContDownlinkBurst::extract(burst).ok().map(DownlinkBurst::Normal);
+            }
+        }
+
+        None
     }
 
-}
\ No newline at end of file
+    /// Check whether the training sequence is present at the position within the buffer, to
+    /// within the error tolerance. If it is, copy out the burst with any errors in the training
+    /// sequence corrected, so that it may be extracted.
+    fn copy_burst(&self, position: usize, training_sequence: &Bits) -> Option<Bits> {
+
+        if self.buffer.count_errors(position, training_sequence, self.max_errors) > self.max_errors {
+            return None;
+        }
+
+        let mut burst = self.buffer.range(0, BURST_BITS);
+        burst[position..position + training_sequence.len()].copy_from_bitslice(training_sequence);
+        Some(burst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::burst::Build;
+    use crate::cell_config::CellConfig;
+    use crate::lower_mac::generate_dl_slot;
+    use crate::tdma_time::TDMATime;
+    use crate::upper_mac::UpperMAC;
+
+    /// Generate some leading noise followed by a number of slots, starting from frame 18
+    fn generate_stream(slots: usize) -> Bits {
+
+        let mut mac = UpperMAC::new(CellConfig::default());
+        let mut stream = Bits::repeat(false, 1000);
+        let mut time = TDMATime::at(0, 17, 0, 0);
+
+        for _ in 0..slots {
+            stream.extend(generate_dl_slot(&mut mac, &time).build());
+            time = time.next();
+        }
+
+        stream
+    }
+
+    #[test]
+    fn synchronises_to_generated_stream() {
+
+        let stream = generate_stream(8);
+        let mut synchroniser = Synchroniser::new(0);
+
+        // Feed in awkwardly-sized chunks
+        let mut bursts = Vec::new();
+        for chunk in stream.chunks(97) {
+            bursts.extend(synchroniser.feed(Bits::from_bitslice(chunk)));
+        }
+
+        assert_eq!(bursts.len(), 8);
+        assert_eq!(*synchroniser.state(), State::Locked);
+
+        for (index, burst) in bursts.iter().enumerate() {
+            assert_eq!(burst.offset, 1000 + index * BURST_BITS);
+        }
+
+        // The second slot of frame 18, multiframe 1 carries the BSCH
+        assert!(matches!(bursts[0].burst, DownlinkBurst::Normal(_)));
+        assert!(matches!(bursts[1].burst, DownlinkBurst::Sync(_)));
+    }
+
+    #[test]
+    fn tolerates_training_sequence_errors() {
+
+        let mut stream = generate_stream(2);
+
+        // Corrupt the training sequence of the first burst
+        let position = 1000 + NORMAL_TRAINING_SEQUENCE_POSITION + 3;
+        let bit = stream[position];
+        stream.set(position, !bit);
+
+        let bursts = Synchroniser::new(0).feed(stream.clone());
+        assert_eq!(bursts.len(), 1);
+        assert_eq!(bursts[0].offset, 1000 + BURST_BITS);
+
+        let bursts = Synchroniser::new(2).feed(stream);
+        assert_eq!(bursts.len(), 2);
+        assert_eq!(bursts[0].offset, 1000);
+    }
+
+    #[test]
+    fn tracks_burst_progress() {
+
+        let stream = generate_stream(2);
+        let mut synchroniser = Synchroniser::new(0);
+        assert_eq!(*synchroniser.state(), State::Unlocked);
+
+        // Locks at the end of the first burst
+        let bursts = synchroniser.feed(Bits::from_bitslice(&stream[..1000 + BURST_BITS]));
+        assert_eq!(bursts.len(), 1);
+        assert_eq!(*synchroniser.state(), State::Locked);
+
+        // Then the second burst starts
+        let bursts = synchroniser.feed(Bits::from_bitslice(&stream[1000 + BURST_BITS..1001 + BURST_BITS]));
+        assert!(bursts.is_empty());
+        assert_eq!(*synchroniser.state(), State::LockedAndBurstStarted);
+
+        // And is extracted once complete, awaiting the next
+        let bursts = synchroniser.feed(Bits::from_bitslice(&stream[1001 + BURST_BITS..]));
+        assert_eq!(bursts.len(), 1);
+        assert_eq!(*synchroniser.state(), State::Locked);
+    }
+}
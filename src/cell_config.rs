@@ -0,0 +1,133 @@
+use crate::lower::scrambler::State;
+use crate::pdu::downlink::{
+    AccessCodeDefinition,
+    BSServiceDetails,
+    Immediate,
+    Offset,
+    OptionalField,
+    RFParameters,
+    SharingMode,
+    TimeslotPointer
+};
+
+/// Configuration of the cell, as broadcast on the BSCH and BNCH
+#[derive(Debug, Clone)]
+pub(crate) struct CellConfig {
+
+    // Identity (BSCH)
+    pub(crate) mcc: u32,
+    pub(crate) mnc: u32,
+    pub(crate) colour_code: u32,
+    pub(crate) sharing_mode: SharingMode,
+
+    // Carrier (SYSINFO)
+    pub(crate) main_carrier: u32,
+    pub(crate) frequency_band: u32,
+    pub(crate) offset: Offset,
+    pub(crate) duplex_spacing: u32,
+    pub(crate) reverse_operation: bool,
+
+    // Access (SYSINFO)
+    pub(crate) rf_parameters: RFParameters,
+    pub(crate) sysinfo_optional_field: OptionalField,
+
+    // Services (MLE SYSINFO)
+    pub(crate) location_area: u32,
+    pub(crate) subscriber_class: u32,
+    pub(crate) bs_service_details: BSServiceDetails
+}
+
+impl CellConfig {
+
+    /// The scrambling code used for every channel other than the BSCH, derived from the cell's
+    /// identity (EN 300 392-2 § 8.2.5.2)
+    pub(crate) fn scrambling_code(&self) -> State {
+        State::new(self.mcc, self.mnc, self.colour_code)
+    }
+
+    /// The frequency of the main carrier on the downlink, in Hz: the frequency band's base of a
+    /// multiple of 100 MHz, plus 25 kHz per carrier number, plus the offset (EN 300 392-2
+    /// § 21.4.4.1)
+    pub(crate) fn downlink_frequency(&self) -> u64 {
+        let nominal = self.frequency_band as i64 * 100_000_000 + self.main_carrier as i64 * 25_000;
+        (nominal + self.offset.hertz()) as u64
+    }
+}
+
+impl Default for CellConfig {
+    fn default() -> Self {
+        CellConfig {
+            mcc: 234,
+            mnc: 0,
+            colour_code: 0,
+            sharing_mode: SharingMode::ContinuousTransmission,
+            main_carrier: 0,
+            frequency_band: 0,
+            offset: Offset::NoOffset,
+            duplex_spacing: 0,
+            reverse_operation: false,
+            rf_parameters: RFParameters {
+                ms_txpwr_max_cell: 0,
+                rxlev_access_min: 0,
+                access_parameter: 0,
+                radio_downlink_timeout: 0
+            },
+            sysinfo_optional_field: OptionalField::DefaultAccessCodeA(AccessCodeDefinition {
+                immediate: Immediate::AlwaysRandomise,
+                waiting_time_opportunities: 0,
+                number_of_attempts: 0,
+                frame_length_x4: false,
+                timeslot: TimeslotPointer::SameAsDownlink,
+                minimum_priority: 0
+            }),
+            location_area: 0,
+            subscriber_class: 0,
+            bs_service_details: BSServiceDetails {
+                registration_required: false,
+                deregistration_required: false,
+                priority_cell: false,
+                cell_never_uses_minimum_mode: false,
+                migration: false,
+                system_wide_services: false,
+                tetra_voice_service: false,
+                circuit_mode_data_service: false,
+                sndcp_service: false,
+                air_interface_encryption_service: false,
+                advanced_link_supported: false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn scrambling_code_follows_identity() {
+
+        let config = CellConfig {
+            mcc: 235,
+            mnc: 91,
+            colour_code: 12,
+            ..CellConfig::default()
+        };
+
+        assert_eq!(config.scrambling_code().state, State::new(235, 91, 12).state);
+        assert_ne!(config.scrambling_code().state, CellConfig::default().scrambling_code().state);
+    }
+
+    #[test]
+    fn downlink_frequency_follows_carrier() {
+
+        let config = CellConfig {
+            main_carrier: 1521,
+            frequency_band: 4,
+            offset: Offset::Minus6_25kHz,
+            ..CellConfig::default()
+        };
+
+        assert_eq!(config.downlink_frequency(), 438_018_750);
+    }
+}
@@ -37,6 +37,21 @@ pub fn add_fill_bits(bits: &mut Bits, capacity: FillBitCapacity) -> usize {
     bits.len() - original_length
 }
 
+/// Remove fill bits added according to ETSI EN 300 392-2 § 23.4.2.2, I.e. the trailing '0' bits
+/// and the '1' bit which precedes them
+///
+/// Returns the number of fill bits removed, which is 0 if there was no '1' bit to be found
+pub fn remove_fill_bits(bits: &mut Bits) -> usize {
+
+    let original_length = bits.len();
+
+    if let Some(fill_start) = bits.last_one() {
+        bits.truncate(fill_start);
+    }
+
+    original_length - bits.len()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -75,6 +90,10 @@ mod tests {
         // Check the fill bits are correct
         assert!(bits[24]);
         assert!(bits[25..32].iter().all(|b| !b));
+
+        // And that they can be removed again
+        assert_eq!(remove_fill_bits(&mut bits), 8);
+        assert_eq!(bits, from_bitstr("11111111 00000000 10101010"));
     }
 
 }
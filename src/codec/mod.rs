@@ -4,22 +4,22 @@ mod fill_bits;
 
 pub use reader::Reader;
 pub use writer::Writer;
-pub use fill_bits::{add_fill_bits, FillBitCapacity};
+pub use fill_bits::{add_fill_bits, remove_fill_bits, FillBitCapacity};
 
 use crate::codec::Optional::{Absent, Present};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Optional<T> {
     Absent,
     Present(T)
 }
 
 impl <T> Decodable for Optional<T> where T: Decodable {
-    fn decode(reader: &mut Reader) -> Self {
-        if reader.read_bool() {
-            Present(T::decode(reader))
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        if reader.read_bool("o_bit")? {
+            Ok(Present(T::decode(reader)?))
         } else {
-            Absent
+            Ok(Absent)
         }
     }
 }
@@ -28,15 +28,40 @@ impl <T> Encodable for Optional<T> where T: Encodable {
     /// Encode the field, including a prefix O-bit
     fn encode(&self, writer: &mut Writer) {
         match self {
-            Present(value) => value.encode(writer),
+            Present(value) => {
+                writer.write_bool(true);
+                value.encode(writer)
+            },
             Absent => writer.write_bool(false)
         }
     }
 }
 
+/// Reasons for which decoding may fail
+#[derive(Debug, PartialEq)]
+pub enum DecodeErrorReason {
+    /// Fewer bits remain than the field requires
+    InsufficientBits {
+        required: usize,
+        remaining: usize
+    },
+    /// The field's value is reserved or otherwise not valid
+    InvalidValue(u32)
+}
+
+/// An error encountered whilst decoding a PDU
+#[derive(Debug, PartialEq)]
+pub struct DecodeError {
+    /// Position of the reader, in bits, at which the error was detected
+    pub position: usize,
+    /// Name of the field being decoded
+    pub field: &'static str,
+    pub reason: DecodeErrorReason
+}
+
 /// Functionality for decoding a PDU from an existing reader
-pub trait Decodable {
-    fn decode(reader: &mut Reader) -> Self;
+pub trait Decodable: Sized {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError>;
 }
 
 /// Functionality for encoding a PDU into an existing reader
@@ -58,7 +83,12 @@ impl <T> Encodable for T where T: num::ToPrimitive + SizedField {
 }
 
 impl <T> Decodable for T where T: num::FromPrimitive + SizedField {
-    fn decode(reader: &mut Reader) -> Self {
-        num::FromPrimitive::from_u32(reader.read_int(Self::size())).unwrap()
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        // Fields of this kind are named after their type
+        let field = std::any::type_name::<T>().rsplit("::").next().unwrap();
+
+        let value = reader.read_int(field, Self::size())?;
+        num::FromPrimitive::from_u32(value).ok_or_else(|| reader.invalid_value(field, value))
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use bitvec::prelude::*;
 use crate::bits::Bits;
+use crate::codec::{DecodeError, DecodeErrorReason};
 
 pub struct Reader<'a> {
     data: &'a Bits,
@@ -17,12 +18,30 @@ impl<'a> Reader<'a> {
         }
     }
 
-    /// Panic due to a bounds check failure
-    fn bounds_check_fail(&self, callee: &str, size: usize) -> ! {
-        panic!(
-            "{}: Attempting to read {size} bits at position {}, but only {} bits remaining, total size is {}",
-            callee, self.position, self.count_remaining(), self.data.len()
-        )
+    /// Create the error for a field which requires more bits than remain
+    fn insufficient_bits(&self, field: &'static str, size: usize) -> DecodeError {
+        DecodeError {
+            position: self.position,
+            field,
+            reason: DecodeErrorReason::InsufficientBits {
+                required: size,
+                remaining: self.count_remaining()
+            }
+        }
+    }
+
+    /// Create the error for a field which has been read, but whose value isn't valid
+    pub fn invalid_value(&self, field: &'static str, value: u32) -> DecodeError {
+        DecodeError {
+            position: self.position,
+            field,
+            reason: DecodeErrorReason::InvalidValue(value)
+        }
+    }
+
+    /// Get the current position of the reader within the data
+    pub fn position(&self) -> usize {
+        self.position
     }
 
     /// Get the number of remaining bits
@@ -31,18 +50,20 @@ impl<'a> Reader<'a> {
     }
     
     /// Skip a number of bits
-    pub fn skip(&mut self, size: usize) {
+    pub fn skip(&mut self, field: &'static str, size: usize) -> Result<(), DecodeError> {
 
         if size > self.count_remaining() {
-            self.bounds_check_fail("skip", size);
+            return Err(self.insufficient_bits(field, size));
         }
 
         // Advance the reader
         self.position += size;
+
+        Ok(())
     }
 
     /// Read an integer, which may be up to 32 bits
-    pub fn read_int(&mut self, size: usize) -> u32 {
+    pub fn read_int(&mut self, field: &'static str, size: usize) -> Result<u32, DecodeError> {
 
         if size > 32 {
             panic!("can't read more than 32 bits, attempting {size}")
@@ -50,7 +71,7 @@ impl<'a> Reader<'a> {
 
         let remaining= self.count_remaining();
         if size > remaining {
-            self.bounds_check_fail("read_int", size);
+            return Err(self.insufficient_bits(field, size));
         }
 
         // Read the bits & load into a u32
@@ -59,21 +80,21 @@ impl<'a> Reader<'a> {
         // Advance the reader
         self.position += size;
 
-        val
+        Ok(val)
     }
 
     /// Read the next bit as a boolean
-    pub fn read_bool(&mut self) -> bool {
+    pub fn read_bool(&mut self, field: &'static str) -> Result<bool, DecodeError> {
 
         if self.count_remaining() < 1 {
-            self.bounds_check_fail("read_bool", 1);
+            return Err(self.insufficient_bits(field, 1));
         }
 
         // Return the bit without advancing
         let result = self.data[self.position];
         self.position += 1;
 
-        result
+        Ok(result)
     }
 
     /// Read the rest of the bits that are available and return them inside a BitVec
@@ -94,10 +115,10 @@ impl<'a> Reader<'a> {
         result
     }
 
-    pub fn read(&mut self, size: usize) -> Bits {
+    pub fn read(&mut self, field: &'static str, size: usize) -> Result<Bits, DecodeError> {
 
         if size > self.count_remaining() {
-            self.bounds_check_fail("read", size);
+            return Err(self.insufficient_bits(field, size));
         }
 
         // Read the bits and return them
@@ -106,7 +127,7 @@ impl<'a> Reader<'a> {
         // Advance the reader
         self.position += size;
 
-        result
+        Ok(result)
     }
 }
 
@@ -129,8 +150,8 @@ mod tests {
         // Create a reader over the data
         let mut cur = Reader::new(&data);
 
-        assert_eq!(cur.read_int(6), 48);
-        assert_eq!(cur.read_int(6), 48);
+        assert_eq!(cur.read_int("first", 6), Ok(48));
+        assert_eq!(cur.read_int("second", 6), Ok(48));
     }
 
     #[test]
@@ -142,9 +163,23 @@ mod tests {
         let mut cur = Reader::new(&data);
 
         // Read the first bit
-        assert!(!cur.read_bool());
-        assert!(cur.read_bool());
-        assert!(!cur.read_bool());
-        assert!(cur.read_bool());
+        assert_eq!(cur.read_bool("first"), Ok(false));
+        assert_eq!(cur.read_bool("second"), Ok(true));
+        assert_eq!(cur.read_bool("third"), Ok(false));
+        assert_eq!(cur.read_bool("fourth"), Ok(true));
+    }
+
+    #[test]
+    fn fails_when_exhausted() {
+
+        let data = from_bitstr("0101");
+        let mut cur = Reader::new(&data);
+
+        cur.skip("first", 3).unwrap();
+        assert_eq!(cur.read_int("second", 2), Err(DecodeError {
+            position: 3,
+            field: "second",
+            reason: DecodeErrorReason::InsufficientBits { required: 2, remaining: 1 }
+        }));
     }
 }
\ No newline at end of file
@@ -0,0 +1,488 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use serde::Deserialize;
+use crate::cell_config::CellConfig;
+use crate::pdu::downlink::{
+    AccessCodeDefinition,
+    BSServiceDetails,
+    ExtendedServicesBroadcast,
+    Immediate,
+    Offset,
+    OptionalField,
+    SharingMode,
+    TimeslotPointer
+};
+use crate::tdma_time::TDMATime;
+
+/// Errors which may occur when loading a configuration file
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Syntax(toml::de::Error),
+    OutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32
+    },
+    InvalidValue {
+        field: &'static str,
+        reason: String
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "unable to read configuration file: {err}"),
+            ConfigError::Syntax(err) => write!(f, "invalid configuration file: {err}"),
+            ConfigError::OutOfRange { field, value, min, max } =>
+                write!(f, "{field} must be between {min} and {max}, got {value}"),
+            ConfigError::InvalidValue { field, reason } => write!(f, "{field} {reason}")
+        }
+    }
+}
+
+/// Configuration of the generator: the cell to simulate and the time at which to start
+pub(crate) struct GeneratorConfig {
+    pub(crate) cell: CellConfig,
+    pub(crate) start_time: TDMATime
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            cell: CellConfig::default(),
+
+            // Start just before the BSCH in the first control frame
+            start_time: TDMATime::at(0, 17, 0, 0)
+        }
+    }
+}
+
+impl GeneratorConfig {
+
+    /// Load and validate the configuration file at the specified path
+    pub(crate) fn load(path: &Path) -> Result<GeneratorConfig, ConfigError> {
+        let source = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::parse(&source)
+    }
+
+    /// Parse and validate a TOML configuration.
+    /// The cell identity is required, anything else which isn't specified takes its default value.
+    pub(crate) fn parse(source: &str) -> Result<GeneratorConfig, ConfigError> {
+
+        let file: ConfigFile = toml::from_str(source).map_err(ConfigError::Syntax)?;
+        let mut config = GeneratorConfig::default();
+        let cell = &mut config.cell;
+
+        // Identity
+        cell.mcc = in_bits("cell.mcc", file.cell.mcc, 10)?;
+        cell.mnc = in_bits("cell.mnc", file.cell.mnc, 14)?;
+        cell.colour_code = in_bits("cell.colour_code", file.cell.colour_code, 6)?;
+        if let Some(sharing_mode) = file.cell.sharing_mode {
+            cell.sharing_mode = match sharing_mode {
+                SharingModeName::Continuous => SharingMode::ContinuousTransmission,
+                SharingModeName::CarrierSharing => SharingMode::CarrierSharing,
+                SharingModeName::McchSharing => SharingMode::MCCHSharing,
+                SharingModeName::TrafficCarrierSharing => SharingMode::TrafficCarrierSharing
+            };
+        }
+
+        // Carrier
+        let carrier = file.carrier;
+        if let Some(main_carrier) = carrier.main_carrier {
+            cell.main_carrier = in_bits("carrier.main_carrier", main_carrier, 12)?;
+        }
+        if let Some(frequency_band) = carrier.frequency_band {
+            cell.frequency_band = in_bits("carrier.frequency_band", frequency_band, 4)?;
+        }
+        if let Some(offset) = carrier.offset {
+            cell.offset = match offset {
+                OffsetName::NoOffset => Offset::NoOffset,
+                OffsetName::Plus6_25kHz => Offset::Plus6_25kHz,
+                OffsetName::Minus6_25kHz => Offset::Minus6_25kHz,
+                OffsetName::Plus12_5kHz => Offset::Plus12_5kHz
+            };
+        }
+        if let Some(duplex_spacing) = carrier.duplex_spacing {
+            cell.duplex_spacing = in_bits("carrier.duplex_spacing", duplex_spacing, 3)?;
+        }
+        if let Some(reverse_operation) = carrier.reverse_operation {
+            cell.reverse_operation = reverse_operation;
+        }
+
+        // RF parameters
+        let rf = file.rf_parameters;
+        let rf_parameters = &mut cell.rf_parameters;
+        if let Some(value) = rf.ms_txpwr_max_cell {
+            rf_parameters.ms_txpwr_max_cell = in_bits("rf_parameters.ms_txpwr_max_cell", value, 3)?;
+        }
+        if let Some(value) = rf.rxlev_access_min {
+            rf_parameters.rxlev_access_min = in_bits("rf_parameters.rxlev_access_min", value, 4)?;
+        }
+        if let Some(value) = rf.access_parameter {
+            rf_parameters.access_parameter = in_bits("rf_parameters.access_parameter", value, 4)?;
+        }
+        if let Some(value) = rf.radio_downlink_timeout {
+            rf_parameters.radio_downlink_timeout = in_bits("rf_parameters.radio_downlink_timeout", value, 4)?;
+        }
+
+        // SYSINFO
+        if let Some(optional_field) = file.sysinfo.optional_field {
+            cell.sysinfo_optional_field = optional_field.validate()?;
+        }
+
+        // MLE SYSINFO
+        let mle_sysinfo = file.mle_sysinfo;
+        if let Some(location_area) = mle_sysinfo.location_area {
+            cell.location_area = in_bits("mle_sysinfo.location_area", location_area, 14)?;
+        }
+        if let Some(subscriber_class) = mle_sysinfo.subscriber_class {
+            cell.subscriber_class = in_bits("mle_sysinfo.subscriber_class", subscriber_class, 16)?;
+        }
+        if let Some(services) = mle_sysinfo.bs_service_details {
+            cell.bs_service_details = services.into();
+        }
+
+        // Start time, 1-based as for the TDMATime getters
+        let start = file.start;
+        config.start_time = TDMATime::at(
+            in_range("start.timeslot", start.timeslot.unwrap_or(1), 1, 4)? - 1,
+            in_range("start.frame", start.frame.unwrap_or(18), 1, 18)? - 1,
+            in_range("start.multiframe", start.multiframe.unwrap_or(1), 1, 60)? - 1,
+            in_range("start.hyperframe", start.hyperframe.unwrap_or(1), 1, 65536)? - 1
+        );
+
+        Ok(config)
+    }
+}
+
+/// Validate that a value lies within the inclusive range
+fn in_range(field: &'static str, value: u32, min: u32, max: u32) -> Result<u32, ConfigError> {
+    if value < min || value > max {
+        return Err(ConfigError::OutOfRange { field, value, min, max });
+    }
+    Ok(value)
+}
+
+/// Validate that a value fits in a field of the specified width
+fn in_bits(field: &'static str, value: u32, width: usize) -> Result<u32, ConfigError> {
+    in_range(field, value, 0, (1 << width) - 1)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    cell: CellSection,
+    #[serde(default)]
+    carrier: CarrierSection,
+    #[serde(default)]
+    rf_parameters: RFParametersSection,
+    #[serde(default)]
+    sysinfo: SysinfoSection,
+    #[serde(default)]
+    mle_sysinfo: MLESysinfoSection,
+    #[serde(default)]
+    start: StartSection
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CellSection {
+    mcc: u32,
+    mnc: u32,
+    colour_code: u32,
+    sharing_mode: Option<SharingModeName>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SharingModeName {
+    Continuous,
+    CarrierSharing,
+    McchSharing,
+    TrafficCarrierSharing
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct CarrierSection {
+    main_carrier: Option<u32>,
+    frequency_band: Option<u32>,
+    offset: Option<OffsetName>,
+    duplex_spacing: Option<u32>,
+    reverse_operation: Option<bool>
+}
+
+#[derive(Deserialize)]
+enum OffsetName {
+    #[serde(rename = "none")]
+    NoOffset,
+    #[serde(rename = "+6.25kHz")]
+    Plus6_25kHz,
+    #[serde(rename = "-6.25kHz")]
+    Minus6_25kHz,
+    #[serde(rename = "+12.5kHz")]
+    Plus12_5kHz
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RFParametersSection {
+    ms_txpwr_max_cell: Option<u32>,
+    rxlev_access_min: Option<u32>,
+    access_parameter: Option<u32>,
+    radio_downlink_timeout: Option<u32>
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct SysinfoSection {
+    optional_field: Option<OptionalFieldSection>
+}
+
+/// The SYSINFO optional field, selected by its `type`
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum OptionalFieldSection {
+    TsModeEvenMultiframe {
+        bitmap: Vec<bool>
+    },
+    TsModeOddMultiframe {
+        bitmap: Vec<bool>
+    },
+    DefaultAccessCodeA {
+        /// 0 to always randomise, 15 for immediate access, otherwise the number of frames
+        immediate: u32,
+        waiting_time_opportunities: u32,
+        number_of_attempts: u32,
+        #[serde(default)]
+        frame_length_x4: bool,
+        /// Timeslots (1 to 4) in which access is permitted, or the same as the downlink if empty
+        #[serde(default)]
+        timeslots: Vec<u32>,
+        minimum_priority: u32
+    },
+    ExtendedServicesBroadcast {
+        security_information: u32,
+        sds_tl_addressing_method: u32
+    }
+}
+
+impl OptionalFieldSection {
+
+    fn validate(self) -> Result<OptionalField, ConfigError> {
+        Ok(match self {
+            OptionalFieldSection::TsModeEvenMultiframe { bitmap } =>
+                OptionalField::TSModeEvenMultiframe(ts_mode_bitmap(bitmap)?),
+            OptionalFieldSection::TsModeOddMultiframe { bitmap } =>
+                OptionalField::TSModeOddMultiframe(ts_mode_bitmap(bitmap)?),
+            OptionalFieldSection::DefaultAccessCodeA {
+                immediate,
+                waiting_time_opportunities,
+                number_of_attempts,
+                frame_length_x4,
+                timeslots,
+                minimum_priority
+            } => OptionalField::DefaultAccessCodeA(AccessCodeDefinition {
+                immediate: match in_bits("sysinfo.optional_field.immediate", immediate, 4)? {
+                    0b0000 => Immediate::AlwaysRandomise,
+                    0b1111 => Immediate::Immediate,
+                    frames => Immediate::AfterFrames(frames)
+                },
+                waiting_time_opportunities: in_bits(
+                    "sysinfo.optional_field.waiting_time_opportunities", waiting_time_opportunities, 4
+                )?,
+                number_of_attempts: in_bits("sysinfo.optional_field.number_of_attempts", number_of_attempts, 4)?,
+                frame_length_x4,
+                timeslot: timeslot_pointer(timeslots)?,
+                minimum_priority: in_bits("sysinfo.optional_field.minimum_priority", minimum_priority, 3)?
+            }),
+            OptionalFieldSection::ExtendedServicesBroadcast {
+                security_information,
+                sds_tl_addressing_method
+            } => OptionalField::ExtendedServicesBroadcast(ExtendedServicesBroadcast {
+                security_information: in_bits("sysinfo.optional_field.security_information", security_information, 8)?,
+                sds_tl_addressing_method: num::FromPrimitive::from_u32(
+                    in_bits("sysinfo.optional_field.sds_tl_addressing_method", sds_tl_addressing_method, 2)?
+                ).unwrap()
+            })
+        })
+    }
+}
+
+fn ts_mode_bitmap(bitmap: Vec<bool>) -> Result<[bool; 20], ConfigError> {
+    let length = bitmap.len();
+    bitmap.try_into().map_err(|_| ConfigError::InvalidValue {
+        field: "sysinfo.optional_field.bitmap",
+        reason: format!("must contain 20 entries, got {length}")
+    })
+}
+
+fn timeslot_pointer(timeslots: Vec<u32>) -> Result<TimeslotPointer, ConfigError> {
+
+    if timeslots.is_empty() {
+        return Ok(TimeslotPointer::SameAsDownlink);
+    }
+
+    for timeslot in &timeslots {
+        in_range("sysinfo.optional_field.timeslots", *timeslot, 1, 4)?;
+    }
+
+    Ok(TimeslotPointer::InTimeslots((
+        timeslots.contains(&1),
+        timeslots.contains(&2),
+        timeslots.contains(&3),
+        timeslots.contains(&4)
+    )))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct MLESysinfoSection {
+    location_area: Option<u32>,
+    subscriber_class: Option<u32>,
+    bs_service_details: Option<BSServiceDetailsSection>
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct BSServiceDetailsSection {
+    registration_required: bool,
+    deregistration_required: bool,
+    priority_cell: bool,
+    cell_never_uses_minimum_mode: bool,
+    migration: bool,
+    system_wide_services: bool,
+    tetra_voice_service: bool,
+    circuit_mode_data_service: bool,
+    sndcp_service: bool,
+    air_interface_encryption_service: bool,
+    advanced_link_supported: bool
+}
+
+impl From<BSServiceDetailsSection> for BSServiceDetails {
+    fn from(section: BSServiceDetailsSection) -> Self {
+        BSServiceDetails {
+            registration_required: section.registration_required,
+            deregistration_required: section.deregistration_required,
+            priority_cell: section.priority_cell,
+            cell_never_uses_minimum_mode: section.cell_never_uses_minimum_mode,
+            migration: section.migration,
+            system_wide_services: section.system_wide_services,
+            tetra_voice_service: section.tetra_voice_service,
+            circuit_mode_data_service: section.circuit_mode_data_service,
+            sndcp_service: section.sndcp_service,
+            air_interface_encryption_service: section.air_interface_encryption_service,
+            advanced_link_supported: section.advanced_link_supported
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct StartSection {
+    timeslot: Option<u32>,
+    frame: Option<u32>,
+    multiframe: Option<u32>,
+    hyperframe: Option<u32>
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parses_example_configuration() {
+
+        let config = GeneratorConfig::parse(include_str!("../cell.example.toml")).unwrap();
+
+        assert_eq!(config.cell.mcc, 235);
+        assert_eq!(config.cell.mnc, 91);
+        assert_eq!(config.cell.colour_code, 12);
+        assert_eq!(config.cell.main_carrier, 1521);
+        assert!(matches!(config.cell.offset, Offset::Minus6_25kHz));
+        assert!(matches!(
+            config.cell.sysinfo_optional_field,
+            OptionalField::DefaultAccessCodeA(AccessCodeDefinition {
+                timeslot: TimeslotPointer::InTimeslots((true, false, false, false)),
+                ..
+            })
+        ));
+        assert_eq!(config.cell.location_area, 1001);
+        assert!(config.cell.bs_service_details.registration_required);
+        assert_eq!(config.start_time.frame(), 18);
+    }
+
+    #[test]
+    fn defaults_all_but_identity() {
+
+        let config = GeneratorConfig::parse("
+            [cell]
+            mcc = 1
+            mnc = 2
+            colour_code = 3
+        ").unwrap();
+
+        assert_eq!(config.cell.main_carrier, CellConfig::default().main_carrier);
+        assert_eq!(config.start_time.slot(), 1);
+
+        // Identity is required
+        assert!(matches!(GeneratorConfig::parse("[cell]\nmcc = 1"), Err(ConfigError::Syntax(_))));
+    }
+
+    #[test]
+    fn accepts_last_hyperframe() {
+
+        let config = GeneratorConfig::parse("
+            [cell]
+            mcc = 1
+            mnc = 2
+            colour_code = 3
+
+            [start]
+            hyperframe = 65536
+        ").unwrap();
+
+        assert_eq!(config.start_time.hyperframe(), 65536);
+
+        // Which wraps around to the first
+        let mut time = config.start_time;
+        for _ in 0..60 * 18 * 4 {
+            time = time.next();
+        }
+        assert_eq!(time.hyperframe(), 1);
+    }
+
+    #[test]
+    fn rejects_out_of_range_fields() {
+
+        let result = GeneratorConfig::parse("
+            [cell]
+            mcc = 1024
+            mnc = 2
+            colour_code = 3
+        ");
+
+        match result {
+            Err(err @ ConfigError::OutOfRange { field: "cell.mcc", value: 1024, .. }) =>
+                assert_eq!(err.to_string(), "cell.mcc must be between 0 and 1023, got 1024"),
+            _ => panic!("expected cell.mcc to be out of range")
+        }
+
+        let result = GeneratorConfig::parse("
+            [cell]
+            mcc = 1
+            mnc = 2
+            colour_code = 3
+
+            [sysinfo.optional_field]
+            type = \"ts_mode_even_multiframe\"
+            bitmap = [true, false]
+        ");
+
+        assert!(matches!(result, Err(ConfigError::InvalidValue { field: "sysinfo.optional_field.bitmap", .. })));
+    }
+}
@@ -0,0 +1,94 @@
+use num_complex::Complex32;
+use crate::bits::Bits;
+use super::modulator::Modulator;
+use super::pulse_shaping::PulseShaper;
+
+/// Generates a continuous stream of complex baseband from successive bursts
+///
+/// The modulator's phase and the pulse shaping filter's state carry over from one burst to the
+/// next, as they would on a continuously transmitted carrier.
+pub struct BasebandGenerator {
+    modulator: Modulator,
+    shaper: PulseShaper
+}
+
+impl BasebandGenerator {
+
+    pub fn new(samples_per_symbol: usize) -> Self {
+        Self {
+            modulator: Modulator::new(),
+            shaper: PulseShaper::new(samples_per_symbol)
+        }
+    }
+
+    /// The rate of the output, in samples per second
+    pub fn sample_rate(&self) -> u32 {
+        self.shaper.sample_rate()
+    }
+
+    /// The delay from a symbol's bits to the centre of its pulse, in samples
+    pub fn delay(&self) -> usize {
+        self.shaper.delay()
+    }
+
+    /// Modulate the bits of the next burst, returning `samples_per_symbol` samples per bit pair
+    pub fn burst(&mut self, bits: &Bits) -> Vec<Complex32> {
+        let symbols = self.modulator.modulate(bits);
+        self.shaper.shape(&symbols)
+    }
+
+    /// Return the remainder of the last burst's pulses
+    pub fn flush(&mut self) -> Vec<Complex32> {
+        self.shaper.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::burst::Build;
+    use crate::cell_config::CellConfig;
+    use crate::dqpsk::demodulator::Demodulator;
+    use crate::dqpsk::pulse_shaping::rrc_taps;
+    use crate::lower_mac::generate_dl_slot;
+    use crate::tdma_time::TDMATime;
+    use crate::upper_mac::UpperMAC;
+
+    #[test]
+    fn slots_form_continuous_stream() {
+
+        let samples_per_symbol = 4;
+        let mut generator = BasebandGenerator::new(samples_per_symbol);
+        let mut mac = UpperMAC::new(CellConfig::default());
+        let mut time = TDMATime::at(0, 17, 0, 0);
+
+        let mut bits = Bits::new();
+        let mut samples = vec![];
+        for _ in 0..3 {
+            let burst = generate_dl_slot(&mut mac, &time).build();
+            samples.extend(generator.burst(&burst));
+            bits.extend_from_bitslice(&burst);
+            time = time.next();
+        }
+        samples.extend(generator.flush());
+
+        // 255 symbols per slot, at the symbol rate of 18 kHz
+        assert_eq!(generator.sample_rate(), 72_000);
+        assert_eq!(samples.len(), (3 * 255 + 8) * samples_per_symbol);
+
+        // Matched filter, then take the centre of each symbol
+        let taps = rrc_taps(samples_per_symbol);
+        let symbols: Vec<Complex32> = (0..bits.len() / 2)
+            .map(|index| {
+                let centre = index * samples_per_symbol + 2 * generator.delay();
+                taps.iter()
+                    .enumerate()
+                    .filter_map(|(offset, tap)| samples.get(centre.checked_sub(offset)?).map(|sample| sample * tap))
+                    .sum()
+            })
+            .collect();
+
+        assert_eq!(Demodulator::new().demodulate(&symbols), bits);
+    }
+}
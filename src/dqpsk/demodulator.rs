@@ -0,0 +1,109 @@
+use num_complex::{Complex, Complex32};
+use crate::bits::{Bits, SoftBits};
+
+/// Differential π/4-DQPSK demodulator, the inverse of the `Modulator`
+///
+/// Each symbol is compared with the one before, so a constant phase offset has no effect. The
+/// change in phase gives the bit pair: the first bit is set if the phase decreased, and the
+/// second if it changed by more than π/2 in either direction.
+pub struct Demodulator {
+    /// The previous I/Q symbol received
+    previous: Complex32
+}
+
+impl Demodulator {
+
+    /// Initialises a demodulator with the same reference phase as a new modulator
+    pub fn new() -> Self {
+        Self {
+            previous: Complex::new(1.0, 0.0)
+        }
+    }
+
+    /// The phase difference from the previous symbol, as a complex value scaled by the
+    /// amplitude of both symbols
+    fn difference(&mut self, symbol: Complex32) -> Complex32 {
+        let difference = symbol * self.previous.conj();
+        self.previous = symbol;
+        difference
+    }
+
+    /// Demodulate a complex I/Q value into a bit-pair (value 0 to 3)
+    pub fn next(&mut self, symbol: Complex32) -> u32 {
+        let difference = self.difference(symbol);
+        ((difference.im < 0.0) as u32) << 1 | (difference.re < 0.0) as u32
+    }
+
+    /// Demodulate a complex I/Q value into a pair of soft bits, with a positive value favouring 0
+    pub fn next_soft(&mut self, symbol: Complex32) -> [f32; 2] {
+        let difference = self.difference(symbol);
+        [difference.im, difference.re]
+    }
+
+    /// Demodulate I/Q values into two bits each
+    pub fn demodulate(&mut self, symbols: &[Complex32]) -> Bits {
+        symbols.iter()
+            .flat_map(|&symbol| {
+                let pair = self.next(symbol);
+                [pair & 0b10 != 0, pair & 0b01 != 0]
+            })
+            .collect()
+    }
+
+    /// Demodulate I/Q values into two soft bits each
+    pub fn demodulate_soft(&mut self, symbols: &[Complex32]) -> SoftBits {
+        symbols.iter()
+            .flat_map(|&symbol| self.next_soft(symbol))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::burst::{Build, NormalContDownlinkBurst};
+    use crate::dqpsk::modulator::Modulator;
+
+    fn burst_bits() -> Bits {
+        NormalContDownlinkBurst {
+            bkn1_bits: (0..216).map(|index| index % 3 == 0).collect(),
+            bkn2_bits: (0..216).map(|index| index % 5 < 2).collect(),
+            bb_bits: (0..30).map(|index| index % 2 == 0).collect(),
+            slot_flag: false
+        }.build()
+    }
+
+    #[test]
+    fn demodulates_modulated_burst() {
+
+        let bits = burst_bits();
+        let symbols = Modulator::new().modulate(&bits);
+        assert_eq!(symbols.len() * 2, bits.len());
+
+        assert_eq!(Demodulator::new().demodulate(&symbols), bits);
+    }
+
+    #[test]
+    fn soft_output_is_independent_of_phase_and_amplitude() {
+
+        let bits = burst_bits();
+
+        // A constant phase offset and attenuation, after the first symbol has set the reference
+        let rotation = Complex32::from_polar(0.25, 1.0);
+        let symbols: Vec<Complex32> = Modulator::new().modulate(&bits)
+            .into_iter()
+            .map(|symbol| symbol * rotation)
+            .collect();
+
+        let mut demodulator = Demodulator::new();
+        demodulator.next_soft(symbols[0]);
+        let soft = demodulator.demodulate_soft(&symbols[1..]);
+
+        assert_eq!(soft.len(), bits.len() - 2);
+        for (soft_bit, bit) in soft.iter().zip(bits[2..].iter()) {
+            assert_eq!(*soft_bit < 0.0, *bit);
+            assert!(soft_bit.abs() > 0.04);
+        }
+    }
+}
@@ -1 +1,11 @@
-mod modulator;
\ No newline at end of file
+mod modulator;
+mod demodulator;
+mod pulse_shaping;
+mod baseband;
+mod receiver;
+
+pub use baseband::BasebandGenerator;
+
+// Nothing drives the receive chain from complex baseband yet, other than its tests
+#[allow(unused_imports)]
+pub use receiver::{Receiver, SyncEstimate};
@@ -1,4 +1,5 @@
 use num_complex::{Complex, Complex32};
+use bitvec::prelude::*;
 
 const NUMBER_OF_SYMBOLS: usize = 4;
 
@@ -24,7 +25,8 @@ const ABSOLUTE_PHASES: [Complex<f32>; NUMBER_OF_PHASES] = [
     Complex::new(0.707, -0.707),
 ];
 
-struct Modulator {
+/// π/4-DQPSK modulator, according to ETSI EN 300 392-2 § 5.5
+pub struct Modulator {
     /// The current phase of the modulator, in multiples of π/4
     phase: u32
 }
@@ -55,6 +57,18 @@ impl Modulator {
         ABSOLUTE_PHASES[self.phase as usize]
     }
 
+    /// Modulate bits, which must be of an even number, into one I/Q value per bit pair
+    pub fn modulate(&mut self, bits: &BitSlice<u8, Msb0>) -> Vec<Complex32> {
+
+        if !bits.len().is_multiple_of(2) {
+            panic!("Invalid number of bits: {}. Must be even.", bits.len());
+        }
+
+        bits.chunks(2)
+            .map(|pair| self.next(pair.load_be::<u32>()))
+            .collect()
+    }
+
 }
 
 #[cfg(test)]
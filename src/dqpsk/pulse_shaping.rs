@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+use num_complex::Complex32;
+
+/// The modulation rate, in symbols per second
+pub const SYMBOL_RATE: u32 = 18_000;
+
+/// Roll-off factor of the root-raised-cosine filter (EN 300 392-2 § 5.5)
+pub const ROLL_OFF: f32 = 0.35;
+
+/// Length of the filter's impulse response, in symbols
+const SPAN_SYMBOLS: usize = 8;
+
+/// Impulse response of the root-raised-cosine filter at `t` symbol periods from its centre
+fn rrc(t: f32) -> f32 {
+
+    if t == 0.0 {
+        return 1.0 - ROLL_OFF + 4.0 * ROLL_OFF / PI;
+    }
+
+    // The general form is undefined at ±1/4β, where it's taken to the limit
+    if (t.abs() - 1.0 / (4.0 * ROLL_OFF)).abs() < 1e-6 {
+        let angle = PI / (4.0 * ROLL_OFF);
+        return ROLL_OFF * FRAC_1_SQRT_2 * ((1.0 + 2.0 / PI) * angle.sin() + (1.0 - 2.0 / PI) * angle.cos());
+    }
+
+    let numerator = (PI * t * (1.0 - ROLL_OFF)).sin() + 4.0 * ROLL_OFF * t * (PI * t * (1.0 + ROLL_OFF)).cos();
+    let denominator = PI * t * (1.0 - (4.0 * ROLL_OFF * t).powi(2));
+    numerator / denominator
+}
+
+/// The taps of a root-raised-cosine filter with the specified number of samples per symbol,
+/// normalised to unit energy. There are an odd number, so the filter delays by exactly half its
+/// length.
+pub fn rrc_taps(samples_per_symbol: usize) -> Vec<f32> {
+
+    let length = SPAN_SYMBOLS * samples_per_symbol + 1;
+    let centre = (length / 2) as f32;
+    let taps: Vec<f32> = (0..length)
+        .map(|index| rrc((index as f32 - centre) / samples_per_symbol as f32))
+        .collect();
+
+    let energy = taps.iter().map(|tap| tap * tap).sum::<f32>().sqrt();
+    taps.into_iter().map(|tap| tap / energy).collect()
+}
+
+/// Interpolates symbols through a root-raised-cosine filter, producing a number of samples for
+/// each symbol with unit average power
+///
+/// The most recent symbols are kept between calls, so consecutive bursts form one continuous
+/// stream.
+pub struct PulseShaper {
+    taps: Vec<f32>,
+    samples_per_symbol: usize,
+    /// The symbols within the span of the filter, most recent first
+    symbols: VecDeque<Complex32>
+}
+
+impl PulseShaper {
+
+    pub fn new(samples_per_symbol: usize) -> Self {
+
+        if samples_per_symbol == 0 {
+            panic!("Invalid samples per symbol: 0. Must be at least 1.");
+        }
+
+        // Interpolation spreads each symbol over several samples, so scale up to keep the power
+        let gain = (samples_per_symbol as f32).sqrt();
+
+        Self {
+            taps: rrc_taps(samples_per_symbol).into_iter().map(|tap| tap * gain).collect(),
+            samples_per_symbol,
+            symbols: VecDeque::from(vec![Complex32::new(0.0, 0.0); SPAN_SYMBOLS + 1])
+        }
+    }
+
+    pub fn samples_per_symbol(&self) -> usize {
+        self.samples_per_symbol
+    }
+
+    /// The rate of the output, in samples per second
+    pub fn sample_rate(&self) -> u32 {
+        SYMBOL_RATE * self.samples_per_symbol as u32
+    }
+
+    /// The delay through the filter, in samples
+    pub fn delay(&self) -> usize {
+        self.taps.len() / 2
+    }
+
+    /// Shape the next symbol, returning `samples_per_symbol` samples
+    pub fn next(&mut self, symbol: Complex32) -> Vec<Complex32> {
+
+        self.symbols.pop_back();
+        self.symbols.push_front(symbol);
+
+        // Only every `samples_per_symbol`th tap lines up with a symbol for each output sample
+        (0..self.samples_per_symbol)
+            .map(|phase| {
+                self.symbols.iter()
+                    .zip(self.taps.iter().skip(phase).step_by(self.samples_per_symbol))
+                    .map(|(symbol, tap)| symbol * tap)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Shape a sequence of symbols
+    pub fn shape(&mut self, symbols: &[Complex32]) -> Vec<Complex32> {
+        symbols.iter()
+            .flat_map(|&symbol| self.next(symbol))
+            .collect()
+    }
+
+    /// Return the rest of the filter's response once there are no more symbols
+    pub fn flush(&mut self) -> Vec<Complex32> {
+        (0..SPAN_SYMBOLS)
+            .flat_map(|_| self.next(Complex32::new(0.0, 0.0)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn taps_are_symmetric_with_unit_energy() {
+
+        // Seven samples per symbol puts a tap exactly at 1/4β
+        let taps = rrc_taps(7);
+        assert_eq!(taps.len(), 57);
+        assert!(taps.iter().zip(taps.iter().rev()).all(|(a, b)| (a - b).abs() < 1e-6));
+        assert!((taps.iter().map(|tap| tap * tap).sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!(taps.iter().all(|tap| tap.is_finite()));
+    }
+
+    #[test]
+    fn matched_filter_recovers_symbols() {
+
+        let samples_per_symbol = 4;
+        let symbols: Vec<Complex32> = (0..40)
+            .map(|index| Complex32::from_polar(1.0, index as f32 * 3.0 * PI / 4.0))
+            .collect();
+
+        let mut shaper = PulseShaper::new(samples_per_symbol);
+        let mut samples = shaper.shape(&symbols);
+        samples.extend(shaper.flush());
+
+        // Filtering again with the same taps makes a raised-cosine, which has no intersymbol
+        // interference at the symbol instants
+        let taps = rrc_taps(samples_per_symbol);
+        let delay = 2 * shaper.delay();
+        for (index, symbol) in symbols.iter().enumerate() {
+            let centre = index * samples_per_symbol + delay;
+            let filtered: Complex32 = taps.iter()
+                .enumerate()
+                .filter_map(|(offset, tap)| samples.get(centre.checked_sub(offset)?).map(|sample| sample * tap))
+                .sum();
+            let recovered = filtered / (samples_per_symbol as f32).sqrt();
+            assert!((recovered - symbol).norm() < 0.05, "symbol {index}: {recovered} != {symbol}");
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::f32::consts::{FRAC_PI_4, PI};
+use num_complex::Complex32;
+use crate::bits::Bits;
+use crate::burst::partial::frequency_correction::frequency_correction_bits;
+use crate::burst::partial::training_sequence::training_sequence_sync_bits;
+use super::demodulator::Demodulator;
+use super::modulator::Modulator;
+use super::pulse_shaping::{rrc_taps, SYMBOL_RATE};
+
+/// Number of symbols in a burst
+const BURST_SYMBOLS: usize = 255;
+
+/// Symbol at which the frequency correction field starts in the synchronisation burst, after
+/// q11-q22 and phase adjustment A
+const FREQUENCY_CORRECTION_START: usize = 7;
+
+/// Symbol at which the synchronisation training sequence starts, after the frequency correction
+/// field and SB1
+const SYNC_TRAINING_START: usize = 107;
+
+/// The least correlation with the known symbols taken to be a synchronisation burst
+const MIN_QUALITY: f32 = 0.8;
+
+/// The timing, frequency and phase of a received synchronisation burst
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncEstimate {
+    /// The index of the burst's first symbol in the matched filter's output
+    pub start: usize,
+    /// The offset of the carrier from its nominal frequency, in Hz
+    pub frequency_offset: f32,
+    /// The carrier phase at the first symbol, in radians. The absolute phase of the symbols isn't
+    /// known, so this is only determined modulo π/4.
+    pub phase: f32,
+    /// How well the burst matched the known symbols, from 0 to 1
+    pub quality: f32
+}
+
+/// Receiver front-end for the synchronisation burst: matched filters the complex baseband, then
+/// correlates against the frequency correction field and synchronisation training sequence to
+/// estimate symbol timing, carrier frequency offset and phase.
+///
+/// The correlation uses the phase change between consecutive symbols, which a frequency offset
+/// only rotates, so timing can be found before the frequency is known. The average rotation then
+/// gives the frequency offset.
+pub struct Receiver {
+    samples_per_symbol: usize,
+    taps: Vec<f32>,
+    /// Symbol number and expected phase change from the previous symbol, for each known symbol
+    known_differences: Vec<(usize, Complex32)>,
+    /// Symbol number and expected value of each symbol of the training sequence, modulated
+    /// from zero phase
+    training_symbols: Vec<(usize, Complex32)>
+}
+
+/// The symbols produced by modulating `bits` starting at zero phase
+fn reference_symbols(bits: &Bits) -> Vec<Complex32> {
+    Modulator::new().modulate(bits)
+}
+
+/// Each symbol's phase change from the one before, starting from zero phase
+fn reference_differences(bits: &Bits) -> Vec<Complex32> {
+    let symbols = reference_symbols(bits);
+    std::iter::once(Complex32::new(1.0, 0.0))
+        .chain(symbols.iter().copied())
+        .zip(symbols.iter())
+        .map(|(previous, symbol)| symbol * previous.conj())
+        .collect()
+}
+
+impl Receiver {
+
+    pub fn new(samples_per_symbol: usize) -> Self {
+
+        let frequency_correction = reference_differences(&frequency_correction_bits());
+        let sync_training = reference_differences(&training_sequence_sync_bits());
+
+        let known_differences = frequency_correction.into_iter()
+            .enumerate()
+            .map(|(index, difference)| (FREQUENCY_CORRECTION_START + index, difference))
+            .chain(sync_training.into_iter()
+                .enumerate()
+                .map(|(index, difference)| (SYNC_TRAINING_START + index, difference)))
+            .collect();
+
+        let training_symbols = reference_symbols(&training_sequence_sync_bits()).into_iter()
+            .enumerate()
+            .map(|(index, symbol)| (SYNC_TRAINING_START + index, symbol))
+            .collect();
+
+        Self {
+            samples_per_symbol,
+            taps: rrc_taps(samples_per_symbol),
+            known_differences,
+            training_symbols
+        }
+    }
+
+    /// Filter complex baseband with the root-raised-cosine filter matching the transmitter's,
+    /// compensating for the filter's delay
+    pub fn matched_filter(&self, samples: &[Complex32]) -> Vec<Complex32> {
+        let delay = self.taps.len() / 2;
+        (0..samples.len())
+            .map(|index| {
+                self.taps.iter()
+                    .enumerate()
+                    .filter_map(|(offset, tap)| {
+                        samples.get((index + delay).checked_sub(offset)?).map(|sample| sample * tap)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// The symbol `symbol` of a burst starting at `start` in the filtered samples
+    fn symbol_at(&self, filtered: &[Complex32], start: usize, symbol: usize) -> Complex32 {
+        filtered[start + symbol * self.samples_per_symbol]
+    }
+
+    /// Correlate the phase changes of the burst starting at `start` with those expected,
+    /// returning the sum and the quality of the match
+    fn correlate(&self, filtered: &[Complex32], start: usize) -> (Complex32, f32) {
+
+        let (sum, magnitude) = self.known_differences.iter()
+            .map(|&(symbol, expected)| {
+                let difference = self.symbol_at(filtered, start, symbol)
+                    * self.symbol_at(filtered, start, symbol - 1).conj();
+                (difference * expected.conj(), difference.norm())
+            })
+            .fold((Complex32::new(0.0, 0.0), 0.0), |(sum, magnitude), (correlation, norm)| {
+                (sum + correlation, magnitude + norm)
+            });
+
+        match magnitude > 0.0 {
+            true => (sum, sum.norm() / magnitude),
+            false => (sum, 0.0)
+        }
+    }
+
+    /// Find the synchronisation burst which best matches the known symbols in the filtered
+    /// samples, if any matches well enough. There must be a symbol before the burst, to
+    /// demodulate its first symbol against.
+    pub fn synchronise(&self, filtered: &[Complex32]) -> Option<SyncEstimate> {
+
+        let last_start = filtered.len().checked_sub((BURST_SYMBOLS - 1) * self.samples_per_symbol + 1)?;
+        let (start, sum, quality) = (self.samples_per_symbol..=last_start)
+            .map(|start| {
+                let (sum, quality) = self.correlate(filtered, start);
+                (start, sum, quality)
+            })
+            .max_by(|(_, a, _), (_, b, _)| a.norm().total_cmp(&b.norm()))?;
+
+        if quality < MIN_QUALITY {
+            return None;
+        }
+
+        // The remaining rotation per symbol is due to the frequency offset
+        let rotation = sum.arg();
+        let frequency_offset = rotation * SYMBOL_RATE as f32 / (2.0 * PI);
+
+        // Having corrected the frequency, the training sequence differs from the reference by
+        // the carrier phase, plus the phase of the symbol before it: an unknown multiple of π/4
+        let training: Complex32 = self.training_symbols.iter()
+            .map(|&(symbol, expected)| {
+                let corrected = self.symbol_at(filtered, start, symbol)
+                    * Complex32::from_polar(1.0, -rotation * symbol as f32);
+                corrected * expected.conj()
+            })
+            .sum();
+        let phase = training.arg() - (training.arg() / FRAC_PI_4).round() * FRAC_PI_4;
+
+        Some(SyncEstimate {
+            start,
+            frequency_offset,
+            phase,
+            quality
+        })
+    }
+
+    /// The symbols of the burst described by `estimate`, corrected for frequency offset and
+    /// phase, starting with the symbol before the burst
+    pub fn symbols(&self, filtered: &[Complex32], estimate: &SyncEstimate) -> Vec<Complex32> {
+        let rotation = 2.0 * PI * estimate.frequency_offset / SYMBOL_RATE as f32;
+        (0..=BURST_SYMBOLS)
+            .map(|index| {
+                let sample = filtered[estimate.start + index * self.samples_per_symbol - self.samples_per_symbol];
+                sample * Complex32::from_polar(1.0, -rotation * (index as f32 - 1.0) - estimate.phase)
+            })
+            .collect()
+    }
+
+    /// Find and demodulate a synchronisation burst in complex baseband
+    pub fn receive_sync_burst(&self, samples: &[Complex32]) -> Option<(SyncEstimate, Bits)> {
+
+        let filtered = self.matched_filter(samples);
+        let estimate = self.synchronise(&filtered)?;
+        let symbols = self.symbols(&filtered, &estimate);
+
+        // The symbol before the burst is the reference for its first
+        let mut demodulator = Demodulator::new();
+        demodulator.next(symbols[0]);
+        let bits = demodulator.demodulate(&symbols[1..]);
+
+        Some((estimate, bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::burst::{Build, DownlinkBurst, Extract, SyncContDownlinkBurst};
+    use crate::cell_config::CellConfig;
+    use crate::dqpsk::BasebandGenerator;
+    use crate::lower_mac::{generate_dl_slot, DownlinkReceiver};
+    use crate::tdma_time::TDMATime;
+    use crate::upper_mac::UpperMAC;
+
+    #[test]
+    fn recovers_sync_burst_with_offsets() {
+
+        let samples_per_symbol = 4;
+        let mut generator = BasebandGenerator::new(samples_per_symbol);
+        let mut mac = UpperMAC::new(CellConfig::default());
+
+        // A normal burst, then the BSCH in timeslot 2 of frame 18, then another normal burst
+        let mut bursts = vec![];
+        let mut samples = vec![Complex32::new(0.0, 0.0); 37];
+        let mut time = TDMATime::at(0, 17, 0, 0);
+        for _ in 0..3 {
+            let burst = generate_dl_slot(&mut mac, &time).build();
+            samples.extend(generator.burst(&burst));
+            bursts.push(burst);
+            time = time.next();
+        }
+        samples.extend(generator.flush());
+
+        // Offset the carrier's frequency and phase
+        let frequency_offset = 420.0;
+        let sample_rate = generator.sample_rate() as f32;
+        let samples: Vec<Complex32> = samples.iter()
+            .enumerate()
+            .map(|(index, sample)| {
+                sample * Complex32::from_polar(1.0, 2.0 * PI * frequency_offset * index as f32 / sample_rate + 1.0)
+            })
+            .collect();
+
+        let receiver = Receiver::new(samples_per_symbol);
+        let (estimate, bits) = receiver.receive_sync_burst(&samples).unwrap();
+
+        assert_eq!(estimate.start, 37 + BURST_SYMBOLS * samples_per_symbol + generator.delay());
+        assert!((estimate.frequency_offset - frequency_offset).abs() < 1.0, "{estimate:?}");
+        assert!(estimate.quality > 0.95);
+
+        // The phase applied at the start of the burst, less a multiple of π/4
+        let phase = 2.0 * PI * frequency_offset * estimate.start as f32 / sample_rate + 1.0;
+        let error = estimate.phase - phase;
+        assert!((error - (error / FRAC_PI_4).round() * FRAC_PI_4).abs() < 0.05, "{estimate:?}");
+        assert_eq!(bits, bursts[1]);
+
+        // The BSCH can then be decoded
+        let burst = DownlinkBurst::Sync(SyncContDownlinkBurst::extract(bits).unwrap());
+        let slot = DownlinkReceiver::new().receive_dl_slot(&burst).unwrap();
+        assert!(slot.sync.is_some());
+    }
+
+    #[test]
+    fn ignores_normal_bursts() {
+
+        let samples_per_symbol = 2;
+        let mut generator = BasebandGenerator::new(samples_per_symbol);
+        let mut mac = UpperMAC::new(CellConfig::default());
+
+        let mut samples = vec![];
+        let mut time = TDMATime::at(0, 3, 0, 0);
+        for _ in 0..3 {
+            samples.extend(generator.burst(&generate_dl_slot(&mut mac, &time).build()));
+            time = time.next();
+        }
+
+        assert!(Receiver::new(samples_per_symbol).receive_sync_burst(&samples).is_none());
+    }
+}
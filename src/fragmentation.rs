@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::mem;
+use crate::bits::Bits;
+use crate::codec::{add_fill_bits, Encodable, FillBitCapacity, Optional, Writer};
+use crate::pdu::downlink::{
+    Address, ChannelAllocation, DownlinkMACPDU, Length, MACEndPDU, MACFragPDU, MACResourcePDU, SlotGranting
+};
+use crate::tdma_time::TDMATime;
+
+/// Size of the MAC-FRAG header
+const MAC_FRAG_HEADER_SIZE: usize = 4;
+
+/// Size of the MAC-END header, excluding the slot granting and channel allocation elements
+const MAC_END_HEADER_SIZE: usize = 11;
+
+/// Splits the TM-SDU of a MAC-RESOURCE PDU across as many MAC blocks as are needed to carry it,
+/// according to ETSI EN 300 392-2 § 23.4.2.1
+///
+/// If the TM-SDU fits in the first MAC block, the MAC-RESOURCE PDU is sent whole. Otherwise, it
+/// starts fragmentation and is followed by MAC-FRAG PDUs filling each block, then a MAC-END PDU
+/// carrying the last fragment. The slot granting and channel allocation elements apply to the
+/// complete TM-SDU, so they're moved to the MAC-END PDU when fragmenting.
+pub(crate) struct Fragmenter {
+    first: Option<MACResourcePDU>,
+    grant_is_on_current_channel: bool,
+    slot_granting: Optional<SlotGranting>,
+    channel_allocation: Optional<ChannelAllocation>,
+    remaining: Bits,
+    complete: bool
+}
+
+impl Fragmenter {
+
+    /// Fragment the TM-SDU carried by `pdu`, the length indication of which is set as needed
+    pub(crate) fn new(mut pdu: MACResourcePDU) -> Self {
+        let remaining = mem::take(&mut pdu.tm_sdu_bits);
+        Fragmenter {
+            grant_is_on_current_channel: pdu.grant_is_on_current_channel,
+            first: Some(pdu),
+            slot_granting: Optional::Absent,
+            channel_allocation: Optional::Absent,
+            remaining,
+            complete: false
+        }
+    }
+
+    /// Whether every fragment of the TM-SDU has been sent
+    pub(crate) fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Generate the next PDU, to be carried by a MAC block with `capacity` bits remaining.
+    ///
+    /// PDUs which continue in the next block fill this one. The final PDU ends on an octet
+    /// boundary (or at the end of the block), leaving any remaining capacity for further PDUs.
+    /// Returns `None` once the TM-SDU is complete.
+    pub(crate) fn next_pdu(&mut self, capacity: usize) -> Option<Bits> {
+        if self.complete {
+            return None;
+        }
+
+        Some(match self.first.take() {
+            Some(pdu) => self.first_pdu(pdu, capacity),
+            None => self.continuation_pdu(capacity)
+        })
+    }
+
+    fn first_pdu(&mut self, mut pdu: MACResourcePDU, capacity: usize) -> Bits {
+
+        // Send the TM-SDU whole if it fits
+        let size = encode(&pdu).len() + self.remaining.len();
+        if size <= capacity {
+            let (octets, padded_size) = length_in_octets(size, capacity);
+            pdu.length = Length::Octets(octets);
+            pdu.fill_bit_indication = padded_size > size;
+            pdu.tm_sdu_bits = mem::take(&mut self.remaining);
+            self.complete = true;
+            return encode_with_fill_bits(&pdu, padded_size);
+        }
+
+        // Otherwise start fragmentation, filling the block
+        self.slot_granting = mem::replace(&mut pdu.slot_granting, Optional::Absent);
+        self.channel_allocation = mem::replace(&mut pdu.channel_allocation, Optional::Absent);
+        pdu.length = Length::StartOfFragmentation;
+        pdu.fill_bit_indication = false;
+
+        let header_size = encode(&pdu).len();
+        assert!(header_size < capacity, "MAC block too small to start fragmentation");
+
+        let rest = self.remaining.split_off(capacity - header_size);
+        pdu.tm_sdu_bits = mem::replace(&mut self.remaining, rest);
+        encode(&pdu)
+    }
+
+    fn continuation_pdu(&mut self, capacity: usize) -> Bits {
+
+        // Finish with MAC-END if the rest of the TM-SDU fits
+        let header_size = MAC_END_HEADER_SIZE
+            + encode(&self.slot_granting).len()
+            + encode(&self.channel_allocation).len();
+        let size = header_size + self.remaining.len();
+
+        if size <= capacity {
+            let (octets, padded_size) = length_in_octets(size, capacity);
+            self.complete = true;
+            return encode_with_fill_bits(&MACEndPDU {
+                fill_bit_indication: padded_size > size,
+                grant_is_on_current_channel: self.grant_is_on_current_channel,
+                length: Length::Octets(octets),
+                slot_granting: mem::replace(&mut self.slot_granting, Optional::Absent),
+                channel_allocation: mem::replace(&mut self.channel_allocation, Optional::Absent),
+                tm_sdu_bits: mem::take(&mut self.remaining)
+            }, padded_size);
+        }
+
+        // Otherwise continue with MAC-FRAG. If the rest of the TM-SDU fits in MAC-FRAG but not
+        // MAC-END, it's followed by a MAC-END with an empty TM-SDU.
+        assert!(MAC_FRAG_HEADER_SIZE < capacity, "MAC block too small to continue fragmentation");
+        let fragment_size = capacity - MAC_FRAG_HEADER_SIZE;
+        let rest = self.remaining.split_off(fragment_size.min(self.remaining.len()));
+        let tm_sdu_bits = mem::replace(&mut self.remaining, rest);
+
+        encode_with_fill_bits(&MACFragPDU {
+            fill_bit_indication: tm_sdu_bits.len() < fragment_size,
+            tm_sdu_bits
+        }, capacity)
+    }
+}
+
+fn encode(value: &impl Encodable) -> Bits {
+    let mut writer = Writer::new();
+    value.encode(&mut writer);
+    writer.done()
+}
+
+fn encode_with_fill_bits(pdu: &impl Encodable, padded_size: usize) -> Bits {
+    let mut bits = encode(pdu);
+    add_fill_bits(&mut bits, FillBitCapacity::Bits(padded_size));
+    bits
+}
+
+/// Length indication of a PDU of `size` bits, and the size it's padded to with fill bits.
+///
+/// A PDU which fills a MAC block not ending on an octet boundary (such as the SCH/HD) indicates a
+/// length rounded up past the end of the block, so it's only filled up to the end of the block.
+fn length_in_octets(size: usize, capacity: usize) -> (usize, usize) {
+    let octets = size.div_ceil(8);
+    (octets, (octets * 8).min(capacity))
+}
+
+/// A fragment of a TM-SDU
+#[derive(Debug)]
+pub(crate) enum Fragment {
+    /// Carried by a MAC-RESOURCE PDU which starts fragmentation
+    First(Bits),
+    /// Carried by a MAC-FRAG PDU
+    Continuation(Bits),
+    /// Carried by a MAC-END PDU
+    Last(Bits)
+}
+
+impl Fragment {
+
+    /// The fragment carried by a downlink PDU, if it's part of a fragmented TM-SDU
+    pub(crate) fn of(pdu: DownlinkMACPDU) -> Option<Fragment> {
+        match pdu {
+            DownlinkMACPDU::MACResource(pdu) if pdu.length == Length::StartOfFragmentation =>
+                Some(Fragment::First(pdu.tm_sdu_bits)),
+            DownlinkMACPDU::MACFrag(pdu) => Some(Fragment::Continuation(pdu.tm_sdu_bits)),
+            DownlinkMACPDU::MACEnd(pdu) => Some(Fragment::Last(pdu.tm_sdu_bits)),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ReassemblyError {
+    /// A fragment was received for an address with no TM-SDU being reassembled
+    NotStarted,
+    /// The previous fragment was received too long ago, so the TM-SDU was discarded
+    TimedOut
+}
+
+struct PartialTMSDU {
+    bits: Bits,
+    last_received: TDMATime
+}
+
+/// Reassembles fragmented TM-SDUs, according to ETSI EN 300 392-2 § 23.4.2.1
+///
+/// MAC-FRAG and MAC-END PDUs carry no address, so the caller identifies the TM-SDU which they
+/// continue from the channel they were received on. A TM-SDU is discarded if a new one is started
+/// for the same address, or if its next fragment doesn't arrive within the timeout. The caller
+/// should also discard it when a MAC block which may have continued it couldn't be decoded.
+pub(crate) struct Reassembler {
+    partial: HashMap<Address, PartialTMSDU>,
+    timeout: u32
+}
+
+impl Reassembler {
+
+    /// Create a reassembler which waits at most `timeout` slots between fragments
+    pub(crate) fn new(timeout: u32) -> Self {
+        Reassembler {
+            partial: HashMap::new(),
+            timeout
+        }
+    }
+
+    /// Add a fragment received at `time` to the TM-SDU for `address`, returning the TM-SDU once
+    /// its last fragment has been received
+    pub(crate) fn receive(&mut self, address: Address, fragment: Fragment, time: &TDMATime) -> Result<Option<Bits>, ReassemblyError> {
+
+        let (bits, last) = match fragment {
+            Fragment::First(bits) => {
+                // Any TM-SDU left incomplete for the address is replaced
+                self.partial.insert(address, PartialTMSDU {
+                    bits,
+                    last_received: time.clone()
+                });
+                return Ok(None);
+            },
+            Fragment::Continuation(bits) => (bits, false),
+            Fragment::Last(bits) => (bits, true)
+        };
+
+        let partial = self.partial.get_mut(&address).ok_or(ReassemblyError::NotStarted)?;
+
+        if time.slots_since(&partial.last_received) > self.timeout {
+            self.partial.remove(&address);
+            return Err(ReassemblyError::TimedOut);
+        }
+
+        partial.bits.extend_from_bitslice(&bits);
+        partial.last_received = time.clone();
+
+        Ok(match last {
+            true => self.partial.remove(&address).map(|partial| partial.bits),
+            false => None
+        })
+    }
+
+    /// Discard the TM-SDU being reassembled for `address`, returning whether there was one
+    pub(crate) fn discard(&mut self, address: &Address) -> bool {
+        self.partial.remove(address).is_some()
+    }
+
+    /// Discard every TM-SDU which has timed out by `time`, returning the number discarded
+    pub(crate) fn discard_expired(&mut self, time: &TDMATime) -> usize {
+        let before = self.partial.len();
+        self.partial.retain(|_, partial| time.slots_since(&partial.last_received) <= self.timeout);
+        before - self.partial.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::codec::{Decodable, Reader};
+
+    fn resource_pdu(tm_sdu_bits: Bits) -> MACResourcePDU {
+        MACResourcePDU {
+            address: Address::SSI { address: 1026 },
+            slot_granting: Optional::Present(SlotGranting::decode(&mut Reader::new(&Bits::repeat(false, 8))).unwrap()),
+            tm_sdu_bits,
+            ..MACResourcePDU::null()
+        }
+    }
+
+    fn tm_sdu(size: usize) -> Bits {
+        (0..size).map(|index| (index * index + 3 * index) % 7 < 3).collect()
+    }
+
+    #[test]
+    fn fragments_and_reassembles() {
+
+        let original = tm_sdu(400);
+        let mut fragmenter = Fragmenter::new(resource_pdu(original.clone()));
+        let mut reassembler = Reassembler::new(8);
+        let mut time = TDMATime::at(0, 3, 0, 0);
+        let mut reassembled = None;
+        let mut blocks = 0;
+
+        // Carry the fragments on the SCH/HD
+        while let Some(pdu_bits) = fragmenter.next_pdu(124) {
+            blocks += 1;
+            assert!(pdu_bits.len() <= 124);
+
+            let pdu = DownlinkMACPDU::parse(&pdu_bits).unwrap();
+            let fragment = Fragment::of(pdu).unwrap();
+            reassembled = reassembler.receive(Address::SSI { address: 1026 }, fragment, &time).unwrap();
+            time = time.next();
+        }
+
+        assert!(fragmenter.is_complete());
+        assert_eq!(blocks, 4);
+        assert_eq!(reassembled, Some(original));
+    }
+
+    #[test]
+    fn sends_short_tm_sdu_whole() {
+
+        let mut fragmenter = Fragmenter::new(resource_pdu(tm_sdu(30)));
+
+        let pdu_bits = fragmenter.next_pdu(268).unwrap();
+        assert!(fragmenter.is_complete());
+        assert!(fragmenter.next_pdu(268).is_none());
+
+        // Padded with fill bits to a whole number of octets
+        let Ok(DownlinkMACPDU::MACResource(pdu)) = DownlinkMACPDU::parse(&pdu_bits) else {
+            panic!("expected MAC-RESOURCE");
+        };
+        assert_eq!(pdu_bits.len(), 88);
+        assert_eq!(pdu.length, Length::Octets(11));
+        assert!(pdu.fill_bit_indication);
+        assert!(matches!(pdu.slot_granting, Optional::Present(_)));
+        assert_eq!(pdu.tm_sdu_bits, tm_sdu(30));
+    }
+
+    #[test]
+    fn discards_incomplete_tm_sdus() {
+
+        let mut reassembler = Reassembler::new(4);
+        let address = || Address::SSI { address: 1026 };
+        let time = TDMATime::at(0, 3, 0, 0);
+
+        assert_eq!(
+            reassembler.receive(address(), Fragment::Last(tm_sdu(8)), &time),
+            Err(ReassemblyError::NotStarted)
+        );
+
+        reassembler.receive(address(), Fragment::First(tm_sdu(8)), &time).unwrap();
+        assert_eq!(
+            reassembler.receive(address(), Fragment::Last(tm_sdu(8)), &TDMATime::at(1, 4, 0, 0)),
+            Err(ReassemblyError::TimedOut)
+        );
+
+        reassembler.receive(address(), Fragment::First(tm_sdu(8)), &time).unwrap();
+        assert_eq!(reassembler.discard_expired(&TDMATime::at(0, 4, 0, 0)), 0);
+        assert_eq!(reassembler.discard_expired(&TDMATime::at(1, 4, 0, 0)), 1);
+    }
+}
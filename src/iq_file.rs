@@ -0,0 +1,245 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use num_complex::Complex32;
+use serde::Serialize;
+use crate::tdma_time::TDMATime;
+
+/// Scale from samples of unit average power to 16-bit integers, leaving headroom for the peaks
+/// of the pulse shaping
+const CS16_SCALE: f32 = 8192.0;
+
+/// Formats in which complex baseband can be written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum IQFormat {
+    /// Interleaved little-endian 32-bit floats
+    Cf32,
+    /// Interleaved little-endian 16-bit integers
+    Cs16,
+    /// A SigMF recording: cf32 samples with a JSON metadata file alongside
+    SigMF
+}
+
+impl IQFormat {
+
+    /// The format indicated by a file's extension, if recognised
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "cf32" | "fc32" => Some(IQFormat::Cf32),
+            "cs16" | "sc16" => Some(IQFormat::Cs16),
+            "sigmf" | "sigmf-data" | "sigmf-meta" => Some(IQFormat::SigMF),
+            _ => None
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SigMFGlobal {
+    #[serde(rename = "core:datatype")]
+    datatype: &'static str,
+    #[serde(rename = "core:sample_rate")]
+    sample_rate: u32,
+    #[serde(rename = "core:version")]
+    version: &'static str,
+    #[serde(rename = "core:description")]
+    description: &'static str,
+    #[serde(rename = "core:recorder")]
+    recorder: &'static str
+}
+
+#[derive(Serialize)]
+struct SigMFCapture {
+    #[serde(rename = "core:sample_start")]
+    sample_start: usize,
+    #[serde(rename = "core:frequency")]
+    frequency: u64
+}
+
+#[derive(Serialize)]
+struct SigMFAnnotation {
+    #[serde(rename = "core:sample_start")]
+    sample_start: usize,
+    #[serde(rename = "core:sample_count")]
+    sample_count: usize,
+    #[serde(rename = "core:label")]
+    label: String
+}
+
+#[derive(Serialize)]
+struct SigMFMetadata {
+    global: SigMFGlobal,
+    captures: Vec<SigMFCapture>,
+    annotations: Vec<SigMFAnnotation>
+}
+
+/// Writes complex baseband to a file, annotating each burst with its TDMA time for SigMF
+pub(crate) struct IQWriter {
+    format: IQFormat,
+    data: BufWriter<File>,
+    /// The path of the SigMF metadata file, if any
+    meta_path: Option<PathBuf>,
+    sample_rate: u32,
+    frequency: u64,
+    /// The delay from a burst's samples being written to its pulses, in samples
+    delay: usize,
+    samples_written: usize,
+    annotations: Vec<SigMFAnnotation>
+}
+
+impl IQWriter {
+
+    /// Create the file(s) for a recording at `sample_rate`, centred on `frequency` in Hz. For
+    /// SigMF, the data and metadata files are named after the path without its extension.
+    pub(crate) fn create(
+        path: &Path,
+        format: IQFormat,
+        sample_rate: u32,
+        frequency: u64,
+        delay: usize
+    ) -> std::io::Result<Self> {
+
+        let (data_path, meta_path) = match format {
+            IQFormat::SigMF => (path.with_extension("sigmf-data"), Some(path.with_extension("sigmf-meta"))),
+            IQFormat::Cf32 | IQFormat::Cs16 => (path.to_path_buf(), None)
+        };
+
+        Ok(IQWriter {
+            format,
+            data: BufWriter::new(File::create(data_path)?),
+            meta_path,
+            sample_rate,
+            frequency,
+            delay,
+            samples_written: 0,
+            annotations: vec![]
+        })
+    }
+
+    /// Write samples which don't belong to a burst, such as the end of the last burst's pulses
+    pub(crate) fn write(&mut self, samples: &[Complex32]) -> std::io::Result<()> {
+
+        for sample in samples {
+            match self.format {
+                IQFormat::Cf32 | IQFormat::SigMF => {
+                    self.data.write_all(&sample.re.to_le_bytes())?;
+                    self.data.write_all(&sample.im.to_le_bytes())?;
+                },
+                IQFormat::Cs16 => {
+                    for component in [sample.re, sample.im] {
+                        let scaled = (component * CS16_SCALE).round().clamp(i16::MIN as f32, i16::MAX as f32);
+                        self.data.write_all(&(scaled as i16).to_le_bytes())?;
+                    }
+                }
+            }
+        }
+
+        self.samples_written += samples.len();
+        Ok(())
+    }
+
+    /// Write the samples of the burst at `time`
+    pub(crate) fn write_burst(&mut self, time: &TDMATime, samples: &[Complex32]) -> std::io::Result<()> {
+
+        self.annotations.push(SigMFAnnotation {
+            sample_start: self.samples_written + self.delay,
+            sample_count: samples.len(),
+            label: format!(
+                "TN {} FN {} MN {} HN {}",
+                time.slot(), time.frame(), time.multiframe(), time.hyperframe()
+            )
+        });
+
+        self.write(samples)
+    }
+
+    /// Flush the samples and write the SigMF metadata, if any
+    pub(crate) fn finish(mut self) -> std::io::Result<()> {
+
+        self.data.flush()?;
+
+        let Some(meta_path) = &self.meta_path else {
+            return Ok(());
+        };
+
+        let metadata = SigMFMetadata {
+            global: SigMFGlobal {
+                datatype: "cf32_le",
+                sample_rate: self.sample_rate,
+                version: "1.0.0",
+                description: "TETRA downlink",
+                recorder: "tetra-mac"
+            },
+            captures: vec![SigMFCapture {
+                sample_start: 0,
+                frequency: self.frequency
+            }],
+            annotations: self.annotations
+        };
+
+        let meta = BufWriter::new(File::create(meta_path)?);
+        serde_json::to_writer_pretty(meta, &metadata)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tetra-mac-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn writes_interleaved_samples() {
+
+        let samples = [Complex32::new(0.5, -0.25), Complex32::new(-8.0, 1.0)];
+
+        let path = temp_path("samples.cf32");
+        let mut writer = IQWriter::create(&path, IQFormat::from_path(&path).unwrap(), 72_000, 0, 0).unwrap();
+        writer.write(&samples).unwrap();
+        writer.finish().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(f32::from_le_bytes(bytes[4..8].try_into().unwrap()), -0.25);
+
+        // Large values saturate rather than wrapping
+        let path = temp_path("samples.cs16");
+        let mut writer = IQWriter::create(&path, IQFormat::from_path(&path).unwrap(), 72_000, 0, 0).unwrap();
+        writer.write(&samples).unwrap();
+        writer.finish().unwrap();
+        let values: Vec<i16> = std::fs::read(&path).unwrap()
+            .chunks(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(values, [4096, -2048, i16::MIN, 8192]);
+    }
+
+    #[test]
+    fn annotates_sigmf_recording() {
+
+        let path = temp_path("recording.sigmf");
+        let mut writer = IQWriter::create(&path, IQFormat::SigMF, 72_000, 438_018_750, 16).unwrap();
+        writer.write_burst(&TDMATime::at(0, 17, 0, 0), &[Complex32::new(1.0, 0.0); 1020]).unwrap();
+        writer.write_burst(&TDMATime::at(1, 17, 0, 0), &[Complex32::new(1.0, 0.0); 1020]).unwrap();
+        writer.finish().unwrap();
+
+        let data_length = std::fs::metadata(path.with_extension("sigmf-data")).unwrap().len();
+        let meta: serde_json::Value = serde_json::from_slice(
+            &std::fs::read(path.with_extension("sigmf-meta")).unwrap()
+        ).unwrap();
+        std::fs::remove_file(path.with_extension("sigmf-data")).unwrap();
+        std::fs::remove_file(path.with_extension("sigmf-meta")).unwrap();
+
+        assert_eq!(data_length, 2040 * 8);
+        assert_eq!(meta["global"]["core:datatype"], "cf32_le");
+        assert_eq!(meta["global"]["core:sample_rate"], 72_000);
+        assert_eq!(meta["captures"][0]["core:frequency"], 438_018_750);
+        assert_eq!(meta["annotations"][1]["core:sample_start"], 1036);
+        assert_eq!(meta["annotations"][1]["core:label"], "TN 2 FN 18 MN 1 HN 1");
+    }
+}
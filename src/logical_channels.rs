@@ -1,19 +1,76 @@
-use crate::bits::Bits;
+use crate::bits::{hard_decision, Bits, SoftBit, SoftBits};
 use crate::lower::rcpc::puncturers::{PredefinedPuncturer, Puncturer};
-use crate::lower::block_coder::{block_decode, block_encode};
-use crate::lower::rm_coder::{rm_decode, rm_encode};
-use crate::lower::rcpc::{rcpc_decode, rcpc_encode};
-use crate::lower::interleaver::{interleaver_decode, interleaver_encode};
-use crate::lower::scrambler::{scrambler_decode, scrambler_encode, State};
+use crate::lower::block_coder::{block_decode, block_encode, BlockError};
+use crate::lower::rm_coder::{rm_decode, rm_decode_soft, rm_encode, ReedMullerDecodeError};
+use crate::lower::rcpc::{rcpc_decode, rcpc_decode_soft, rcpc_encode};
+use crate::lower::interleaver::{
+    interleaver_decode,
+    interleaver_decode_soft,
+    interleaver_encode,
+    InterleaverEncodeError,
+    OverNBlocksInterleaver
+};
+use crate::lower::scrambler::{scrambler_decode, scrambler_decode_soft, scrambler_encode, State};
 
 pub enum InitialCode {
     RMCode,
     BlockCode
 }
 
+#[derive(Debug, PartialEq)]
 pub enum InterleaverBehaviour {
     Block { k: usize, a: usize },
-    OverNBlocks
+    OverNBlocks { k: usize, n: usize }
+}
+
+/// Depth over which traffic channel blocks are interleaved (EN 300 392-2 § 8.2.4.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterleavingDepth {
+    N1,
+    N4,
+    N8
+}
+
+impl InterleavingDepth {
+    /// The number of blocks N
+    pub fn blocks(&self) -> usize {
+        match self {
+            InterleavingDepth::N1 => 1,
+            InterleavingDepth::N4 => 4,
+            InterleavingDepth::N8 => 8
+        }
+    }
+
+    /// The interleaver for a traffic channel at this depth. A single block is block interleaved
+    /// as K=432, a=103, so only greater depths need interleaver state.
+    fn interleaver(&self) -> InterleaverBehaviour {
+        match self {
+            InterleavingDepth::N1 => InterleaverBehaviour::Block { k: 432, a: 103 },
+            depth => InterleaverBehaviour::OverNBlocks { k: 432, n: depth.blocks() }
+        }
+    }
+}
+
+/// Problems with the interleaver state provided for a channel interleaved over N blocks
+#[derive(Debug, PartialEq)]
+pub enum InterleaverStateError {
+    /// The channel is interleaved over N blocks, but no state was provided
+    Missing,
+    /// The state is for a different block size or depth than the channel's
+    Mismatched {
+        expected: (usize, usize),
+        provided: (usize, usize)
+    }
+}
+
+/// Errors which may be detected when decoding a logical channel
+#[derive(Debug)]
+pub enum LogicalChannelDecodeError {
+    BlockCode(BlockError),
+    ReedMuller(ReedMullerDecodeError),
+    InterleaverState(InterleaverStateError),
+    /// The block isn't the size the channel's interleaver expects
+    Interleaver(InterleaverEncodeError)
 }
 
 pub struct ChannelProperties {
@@ -27,14 +84,15 @@ pub struct ChannelProperties {
 pub(crate) enum LogicalChannel {
     AccessAssignment,
     BroadcastSynchronisation,
-    TrafficHighProtection,
-    TrafficLowProtection,
+    TrafficHighProtection(InterleavingDepth),
+    TrafficLowProtection(InterleavingDepth),
     TrafficUnprotected,
     SignallingHalfDownlink,
     BroadcastNetwork,
     Stealing,
     SignallingHalfUplink,
-    SignallingFull
+    SignallingFull,
+    CommonLinearisation
 }
 
 impl ChannelProperties {
@@ -54,18 +112,18 @@ impl ChannelProperties {
                 interleaver: Some(InterleaverBehaviour::Block { k: 120, a: 11 }),
                 scrambling: true,
             },
-            LogicalChannel::TrafficHighProtection => ChannelProperties {
+            LogicalChannel::TrafficHighProtection(depth) => ChannelProperties {
                 initial_code: None,
                 rcpc: Some(PredefinedPuncturer::Rate148Over432Puncturer),
                 tail_bits: 4,
-                interleaver: Some(InterleaverBehaviour::OverNBlocks),
+                interleaver: Some(depth.interleaver()),
                 scrambling: true,
             },
-            LogicalChannel::TrafficLowProtection => ChannelProperties {
+            LogicalChannel::TrafficLowProtection(depth) => ChannelProperties {
                 initial_code: None,
                 rcpc: Some(PredefinedPuncturer::Rate292Over432Puncturer),
                 tail_bits: 4,
-                interleaver: Some(InterleaverBehaviour::OverNBlocks),
+                interleaver: Some(depth.interleaver()),
                 scrambling: true,
             },
             LogicalChannel::TrafficUnprotected => ChannelProperties {
@@ -97,6 +155,13 @@ impl ChannelProperties {
                 tail_bits: 4,
                 interleaver: Some(InterleaverBehaviour::Block { k: 432, a: 103 }),
                 scrambling: true,
+            },
+            LogicalChannel::CommonLinearisation => ChannelProperties {
+                initial_code: None,
+                rcpc: None,
+                tail_bits: 0,
+                interleaver: None,
+                scrambling: false,
             }
         }
     }
@@ -104,8 +169,31 @@ impl ChannelProperties {
 
 impl LogicalChannel {
 
-    /// Generate this channel from bits, applying the appropriate encoding chain
-    pub fn encode(&self, type1_bits: Bits, scrambler_state: &State) -> Bits {
+    /// Create the interleaver state required to code this channel, if it is interleaved over
+    /// N blocks. The state must be retained and passed in for every block on the channel.
+    /// Use an interleaver of soft bits to decode soft decisions.
+    pub fn interleaver<T: Copy + Default>(&self) -> Option<OverNBlocksInterleaver<T>> {
+        match ChannelProperties::build(self).interleaver {
+            Some(InterleaverBehaviour::OverNBlocks { k, n }) =>
+                Some(OverNBlocksInterleaver::new(k, n).unwrap()),
+            _ => None
+        }
+    }
+
+    /// Generate this channel from bits, applying the appropriate encoding chain.
+    /// Fails for channels interleaved over N blocks, which need interleaver state.
+    pub fn encode(&self, type1_bits: Bits, scrambler_state: &State) -> Result<Bits, InterleaverStateError> {
+        self.encode_with_interleaver(type1_bits, scrambler_state, None)
+    }
+
+    /// Generate this channel from bits, using the provided state for channels which are
+    /// interleaved over N blocks
+    pub fn encode_with_interleaver(
+        &self,
+        type1_bits: Bits,
+        scrambler_state: &State,
+        interleaver: Option<&mut OverNBlocksInterleaver>
+    ) -> Result<Bits, InterleaverStateError> {
 
         // Resolve channel props
         let chan_props = ChannelProperties::build(self);
@@ -132,20 +220,35 @@ impl LogicalChannel {
         // Interleaving?
         let type4_bits = match chan_props.interleaver {
             Some(InterleaverBehaviour::Block {k, a}) => interleaver_encode(&type3_bits, k, a).unwrap(),
-            Some(InterleaverBehaviour::OverNBlocks) => todo!("over-N-blocks interleaving not yet supported"),
+            Some(InterleaverBehaviour::OverNBlocks {k, n}) =>
+                over_n_blocks_state(interleaver, k, n)?.encode(&type3_bits).unwrap(),
             None => type3_bits
         };
 
         // Scrambling?
         if chan_props.scrambling {
-            scrambler_encode(&type4_bits, scrambler_state)
+            Ok(scrambler_encode(&type4_bits, scrambler_state))
         } else {
-            type4_bits
+            Ok(type4_bits)
         }
     }
 
-    /// Decode this channel from bits, applying the appropriate decoding chain
-    pub fn decode(&self, type5_bits: Bits, scrambler_state: &State) -> Bits {
+    /// Decode this channel from bits, applying the appropriate decoding chain.
+    /// Fails if the initial code indicates that the block was received in error, or if the
+    /// channel is interleaved over N blocks, which needs interleaver state.
+    pub fn decode(&self, type5_bits: Bits, scrambler_state: &State) -> Result<Bits, LogicalChannelDecodeError> {
+        self.decode_with_interleaver(type5_bits, scrambler_state, None)
+    }
+
+    /// Decode this channel from bits, using the provided state for channels which are
+    /// interleaved over N blocks.
+    /// For those channels the decoded block is the one sent N-1 blocks before this one.
+    pub fn decode_with_interleaver(
+        &self,
+        type5_bits: Bits,
+        scrambler_state: &State,
+        interleaver: Option<&mut OverNBlocksInterleaver>
+    ) -> Result<Bits, LogicalChannelDecodeError> {
 
         // Resolve channel props
         let chan_props = ChannelProperties::build(self);
@@ -160,37 +263,148 @@ impl LogicalChannel {
 
         // Interleaving?
         let type3_bits = match chan_props.interleaver {
-            Some(InterleaverBehaviour::Block {k, a}) => interleaver_decode(&type4_bits, k, a),
-            Some(InterleaverBehaviour::OverNBlocks) => todo!("over-N-blocks interleaving not yet supported"),
+            Some(InterleaverBehaviour::Block {k, a}) => {
+                check_block_size(type4_bits.len(), k)?;
+                interleaver_decode(&type4_bits, k, a)
+            },
+            Some(InterleaverBehaviour::OverNBlocks {k, n}) =>
+                over_n_blocks_state(interleaver, k, n)
+                    .map_err(LogicalChannelDecodeError::InterleaverState)?
+                    .decode(&type4_bits)
+                    .map_err(LogicalChannelDecodeError::Interleaver)?,
             None => type4_bits
         };
 
         // RCPC?
-        let mut type2_bits = match chan_props.rcpc {
+        let type2_bits = match &chan_props.rcpc {
             Some(predefined_punc) =>
-                rcpc_decode(&type3_bits, Some(&Puncturer::build(&predefined_punc))),
+                rcpc_decode(&type3_bits, Some(&Puncturer::build(predefined_punc))),
             None => type3_bits
         };
 
-        // Strip tail bits?
-        if chan_props.tail_bits != 0 {
-            type2_bits.truncate(type2_bits.len() - chan_props.tail_bits);
-        }
+        decode_type2(&chan_props, type2_bits)
+    }
 
-        // Initial coding?
-        match chan_props.initial_code {
-            Some(InitialCode::RMCode) => rm_decode(&type2_bits).unwrap(),
-            Some(InitialCode::BlockCode) => block_decode(&type2_bits).unwrap(),
-            None => type2_bits
-        }
+    /// Decode this channel from soft bits, applying the appropriate decoding chain.
+    /// The reliability of each bit is used when Viterbi-decoding channels with an RCPC code,
+    /// otherwise a hard decision is made on each bit.
+    pub fn decode_soft<T: SoftBit>(
+        &self,
+        type5_bits: &[T],
+        scrambler_state: &State
+    ) -> Result<Bits, LogicalChannelDecodeError> {
+        self.decode_soft_with_interleaver(type5_bits, scrambler_state, None)
+    }
+
+    /// Decode this channel from soft bits, using the provided soft interleaver state for channels
+    /// which are interleaved over N blocks.
+    pub fn decode_soft_with_interleaver<T: SoftBit>(
+        &self,
+        type5_bits: &[T],
+        scrambler_state: &State,
+        interleaver: Option<&mut OverNBlocksInterleaver<f32>>
+    ) -> Result<Bits, LogicalChannelDecodeError> {
+
+        // Resolve channel props
+        let chan_props = ChannelProperties::build(self);
+        let type5_bits: SoftBits = type5_bits.iter().map(|bit| bit.llr()).collect();
+
+        // Scrambling?
+        let type4_bits = if chan_props.scrambling {
+            scrambler_decode_soft(&type5_bits, scrambler_state)
+        } else {
+            type5_bits
+        };
+
+        // Interleaving?
+        let type3_bits = match chan_props.interleaver {
+            Some(InterleaverBehaviour::Block {k, a}) => {
+                check_block_size(type4_bits.len(), k)?;
+                interleaver_decode_soft(&type4_bits, k, a)
+            },
+            Some(InterleaverBehaviour::OverNBlocks {k, n}) =>
+                over_n_blocks_state(interleaver, k, n)
+                    .map_err(LogicalChannelDecodeError::InterleaverState)?
+                    .decode_soft(&type4_bits)
+                    .map_err(LogicalChannelDecodeError::Interleaver)?,
+            None => type4_bits
+        };
+
+        // RCPC? Otherwise, a hard decision is all that's needed unless the Reed-Muller code can
+        // make use of the soft bits directly
+        let type2_bits = match (&chan_props.rcpc, &chan_props.initial_code) {
+            (Some(predefined_punc), _) =>
+                rcpc_decode_soft(&type3_bits, Some(&Puncturer::build(predefined_punc))),
+            (None, Some(InitialCode::RMCode)) => return rm_decode_soft(&type3_bits)
+                .map(|decoded| decoded.payload)
+                .map_err(LogicalChannelDecodeError::ReedMuller),
+            (None, _) => hard_decision(&type3_bits)
+        };
+
+        decode_type2(&chan_props, type2_bits)
     }
 }
 
+/// Strip any tail bits from decoded type-2 bits and check the initial code, if there is one
+fn decode_type2(
+    chan_props: &ChannelProperties,
+    mut type2_bits: Bits
+) -> Result<Bits, LogicalChannelDecodeError> {
+
+    // Strip tail bits?
+    if chan_props.tail_bits != 0 {
+        type2_bits.truncate(type2_bits.len() - chan_props.tail_bits);
+    }
+
+    // Initial coding?
+    match chan_props.initial_code {
+        Some(InitialCode::RMCode) => rm_decode(&type2_bits)
+            .map(|decoded| decoded.payload)
+            .map_err(LogicalChannelDecodeError::ReedMuller),
+        Some(InitialCode::BlockCode) => block_decode(&type2_bits).map_err(LogicalChannelDecodeError::BlockCode),
+        None => Ok(type2_bits)
+    }
+}
+
+/// Validate that a received block is the size the block interleaver expects
+fn check_block_size(actual: usize, expected: usize) -> Result<(), LogicalChannelDecodeError> {
+    match actual == expected {
+        true => Ok(()),
+        false => Err(LogicalChannelDecodeError::Interleaver(
+            InterleaverEncodeError::InvalidBlockSize { expected, actual }
+        ))
+    }
+}
+
+/// Validate that interleaver state was provided for a channel interleaved over N blocks, and that
+/// it matches the channel's properties
+fn over_n_blocks_state<T: Copy + Default>(
+    interleaver: Option<&mut OverNBlocksInterleaver<T>>,
+    k: usize,
+    n: usize
+) -> Result<&mut OverNBlocksInterleaver<T>, InterleaverStateError> {
+
+    let interleaver = interleaver.ok_or(InterleaverStateError::Missing)?;
+
+    if interleaver.k() != k || interleaver.n() != n {
+        return Err(InterleaverStateError::Mismatched {
+            expected: (k, n),
+            provided: (interleaver.k(), interleaver.n())
+        });
+    }
+
+    Ok(interleaver)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::bits::from_bitstr;
-    use crate::bits::{Bits};
-    use crate::logical_channels::LogicalChannel;
+    use crate::bits::{to_soft_bits, Bits};
+    use crate::lower::interleaver::InterleaverEncodeError;
+    use crate::logical_channels::{
+        ChannelProperties, InterleaverBehaviour, InterleaverStateError, InterleavingDepth, LogicalChannel,
+        LogicalChannelDecodeError
+    };
     use crate::pdu::downlink::{MLESyncPDU, Sync};
     use crate::codec::{Decodable, Reader};
     use crate::lower::scrambler::State;
@@ -207,12 +421,12 @@ mod tests {
         println!("Pattern {:?}", pattern);
 
         let lch = LogicalChannel::BroadcastSynchronisation;
-        let encoded_bits = lch.encode(pattern.clone(), &scrambler_state);
+        let encoded_bits = lch.encode(pattern.clone(), &scrambler_state).unwrap();
         assert_eq!(encoded_bits.len(), 120);
         println!("Encoded {:?}", encoded_bits);
 
         // Decode the channel
-        let decoded_bits = lch.decode(encoded_bits, &scrambler_state);
+        let decoded_bits = lch.decode(encoded_bits, &scrambler_state).unwrap();
         println!("Decoded {:?}", decoded_bits);
 
         // Should be the same
@@ -237,16 +451,149 @@ mod tests {
 
         // Try decoding
         let lch = LogicalChannel::BroadcastSynchronisation;
-        let decoded_bits = lch.decode(chan_bits, &scrambler_state);
+        let decoded_bits = lch.decode(chan_bits, &scrambler_state).unwrap();
 
         // This will be removed or move to integration tests later, but try decoding the sync PDU
         let mut reader = Reader::new(&decoded_bits);
-        let sync = Sync::decode(&mut reader);
+        let sync = Sync::decode(&mut reader).unwrap();
         println!("{:?}", sync);
 
         // Decode the MLE sync info
         let mut reader = Reader::new(&sync.tm_sdu_bits);
-        let mle_sync = MLESyncPDU::decode(&mut reader);
+        let mle_sync = MLESyncPDU::decode(&mut reader).unwrap();
         println!("{:?}", mle_sync);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn soft_decoding_uses_reliability() {
+
+        let scrambler_state = State::new(234, 30, 17);
+        let lch = LogicalChannel::SignallingHalfDownlink;
+
+        let mut block = Bits::repeat(false, 124);
+        block.fill_with(|idx| idx % 5 < 2);
+        let encoded = lch.encode(block.clone(), &scrambler_state).unwrap();
+
+        // Flip around a fifth of the bits, but mark the flipped bits as unreliable
+        let mut corrupted = encoded.clone();
+        let mut soft = vec![0.0f32; encoded.len()];
+        for (index, bit) in encoded.iter().enumerate() {
+            let flip = index % 8 == 0;
+            corrupted.set(index, *bit ^ flip);
+            let reliability = if flip { 0.25 } else { 4.0 };
+            soft[index] = if *bit ^ flip { -reliability } else { reliability };
+        }
+
+        // Too many errors for hard decisions, but soft decisions recover the block
+        assert!(lch.decode(corrupted, &scrambler_state).is_err());
+        assert_eq!(lch.decode_soft(&soft, &scrambler_state).unwrap(), block);
+
+        // Integer soft bits work the same way
+        let soft_i8: Vec<i8> = soft.iter().map(|llr| (llr * 4.0) as i8).collect();
+        assert_eq!(lch.decode_soft(&soft_i8, &scrambler_state).unwrap(), block);
+    }
+
+    #[test]
+    fn traffic_channel_encode_decode_consistent() {
+
+        let scrambler_state = State::new(234, 30, 17);
+
+        for (lch, type1_len) in [
+            (LogicalChannel::TrafficHighProtection(InterleavingDepth::N8), 144),
+            (LogicalChannel::TrafficLowProtection(InterleavingDepth::N1), 288),
+            (LogicalChannel::TrafficLowProtection(InterleavingDepth::N4), 288),
+        ] {
+
+            // A single block is block interleaved, so needs no state and isn't delayed
+            let n = match &lch {
+                LogicalChannel::TrafficHighProtection(InterleavingDepth::N1) |
+                LogicalChannel::TrafficLowProtection(InterleavingDepth::N1) => 1,
+                LogicalChannel::TrafficHighProtection(depth) |
+                LogicalChannel::TrafficLowProtection(depth) => depth.blocks(),
+                _ => unreachable!()
+            };
+            assert_eq!(lch.interleaver::<bool>().is_none(), n == 1);
+
+            let mut encoder = lch.interleaver();
+            let mut decoder = lch.interleaver();
+            let mut soft_decoder = lch.interleaver();
+
+            // Generate a sequence of distinct blocks
+            let blocks: Vec<Bits> = (0..10)
+                .map(|b| {
+                    let mut block = Bits::repeat(false, type1_len);
+                    block.fill_with(|idx| (idx + b) % 3 == 0);
+                    block
+                })
+                .collect();
+
+            for (index, block) in blocks.iter().enumerate() {
+
+                let encoded = lch.encode_with_interleaver(block.clone(), &scrambler_state, encoder.as_mut()).unwrap();
+                assert_eq!(encoded.len(), 432);
+
+                let decoded_soft = lch.decode_soft_with_interleaver(
+                    &to_soft_bits(&encoded), &scrambler_state, soft_decoder.as_mut()
+                ).unwrap();
+                let decoded = lch.decode_with_interleaver(encoded, &scrambler_state, decoder.as_mut()).unwrap();
+
+                // Decoding is delayed by N-1 blocks
+                if index + 1 >= n {
+                    assert_eq!(decoded, blocks[index + 1 - n]);
+                    assert_eq!(decoded_soft, blocks[index + 1 - n]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn requires_interleaver_state_over_n_blocks() {
+
+        let scrambler_state = State::new(234, 30, 17);
+        let lch = LogicalChannel::TrafficHighProtection(InterleavingDepth::N4);
+
+        assert_eq!(lch.encode(Bits::repeat(false, 144), &scrambler_state), Err(InterleaverStateError::Missing));
+        assert!(matches!(
+            lch.decode(Bits::repeat(false, 432), &scrambler_state),
+            Err(LogicalChannelDecodeError::InterleaverState(InterleaverStateError::Missing))
+        ));
+
+        let mut wrong_depth = LogicalChannel::TrafficHighProtection(InterleavingDepth::N8).interleaver().unwrap();
+        assert_eq!(
+            lch.encode_with_interleaver(Bits::repeat(false, 144), &scrambler_state, Some(&mut wrong_depth)),
+            Err(InterleaverStateError::Mismatched { expected: (432, 4), provided: (432, 8) })
+        );
+
+        // A single block is block interleaved, with the (432, 103) interleaver
+        let lch = LogicalChannel::TrafficHighProtection(InterleavingDepth::N1);
+        assert_eq!(
+            ChannelProperties::build(&lch).interleaver,
+            Some(InterleaverBehaviour::Block { k: 432, a: 103 })
+        );
+        let block: Bits = (0..144).map(|index| index % 7 < 3).collect();
+        let encoded = lch.encode(block.clone(), &scrambler_state).unwrap();
+        assert_eq!(lch.decode(encoded, &scrambler_state).unwrap(), block);
+    }
+
+    #[test]
+    fn rejects_blocks_of_wrong_size() {
+
+        let scrambler_state = State::new(234, 30, 17);
+        let lch = LogicalChannel::TrafficHighProtection(InterleavingDepth::N4);
+
+        let mut decoder = lch.interleaver().unwrap();
+        assert!(matches!(
+            lch.decode_with_interleaver(Bits::repeat(false, 431), &scrambler_state, Some(&mut decoder)),
+            Err(LogicalChannelDecodeError::Interleaver(InterleaverEncodeError::InvalidBlockSize { expected: 432, actual: 431 }))
+        ));
+        let mut soft_decoder = lch.interleaver().unwrap();
+        assert!(matches!(
+            lch.decode_soft_with_interleaver(&[1.0; 433], &scrambler_state, Some(&mut soft_decoder)),
+            Err(LogicalChannelDecodeError::Interleaver(InterleaverEncodeError::InvalidBlockSize { expected: 432, actual: 433 }))
+        ));
+        assert!(matches!(
+            LogicalChannel::SignallingFull.decode(Bits::repeat(false, 400), &scrambler_state),
+            Err(LogicalChannelDecodeError::Interleaver(InterleaverEncodeError::InvalidBlockSize { expected: 432, actual: 400 }))
+        ));
+    }
+}
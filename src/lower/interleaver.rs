@@ -1,4 +1,5 @@
-use crate::bits::Bits;
+use std::collections::VecDeque;
+use crate::bits::{Bits, SoftBits};
 
 fn interleave_bit(k: usize, a: usize, index: usize) -> usize {
     1 + ((a * index) % k)
@@ -7,6 +8,7 @@ fn interleave_bit(k: usize, a: usize, index: usize) -> usize {
 #[derive(Debug)]
 pub enum InterleaverEncodeError {
     InvalidBlockSize { expected: usize, actual: usize },
+    InvalidDepth { k: usize, n: usize },
 }
 
 pub fn interleaver_encode(block: &Bits, k: usize, a: usize) -> Result<Bits, InterleaverEncodeError> {
@@ -44,7 +46,139 @@ pub fn interleaver_decode(block: &Bits, k: usize, a: usize) -> Bits {
 
     deinterleaved
 }
-        
+
+/// De-interleave a block of soft bits, as for [`interleaver_decode`]
+pub fn interleaver_decode_soft(block: &[f32], k: usize, a: usize) -> SoftBits {
+    (0..k)
+        .map(|out_index| block[interleave_bit(k, a, out_index + 1) - 1])
+        .collect()
+}
+
+/// Find the source of type-4 bit `j` (1-based) when interleaving over N blocks.
+///
+/// Returns the number of blocks back the source type-3 block lies (0 being the current block),
+/// plus the 1-based index of the bit within that block.
+/// EN 300 392-2 § 8.2.4.2
+fn over_n_blocks_source(k: usize, n: usize, j: usize) -> (usize, usize) {
+    let blocks_back = (j - 1) % n;
+    let index = ((j - 1) / n) + (blocks_back * (k / n)) + 1;
+    (blocks_back, index)
+}
+
+/// Stateful interleaver for interleaving over N blocks (EN 300 392-2 § 8.2.4.2).
+///
+/// Each type-3 block of K bits is spread over N consecutive type-4 blocks, so the interleaver
+/// must be retained between bursts. The same structure is used on the transmit side (holding
+/// previous type-3 blocks) and the receive side (holding previous type-4 blocks).
+/// The history holds hard bits by default, or soft bits when de-interleaving soft decisions.
+pub struct OverNBlocksInterleaver<T = bool> {
+    k: usize,
+    n: usize,
+    history: VecDeque<Vec<T>>
+}
+
+impl<T: Copy + Default> OverNBlocksInterleaver<T> {
+
+    /// Create a new interleaver for blocks of `k` bits over a depth of `n` blocks
+    /// The history is initially filled with all-zero blocks.
+    pub fn new(k: usize, n: usize) -> Result<Self, InterleaverEncodeError> {
+
+        // K must divide exactly into N parts
+        if n == 0 || !k.is_multiple_of(n) {
+            return Err(InterleaverEncodeError::InvalidDepth { k, n })
+        }
+
+        Ok(Self {
+            k,
+            n,
+            history: VecDeque::from(vec![vec![T::default(); k]; n])
+        })
+    }
+
+    /// Block size (K) this interleaver operates on
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Interleaving depth (N) of this interleaver
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Push a new block into the history, ageing-out the oldest
+    fn push(&mut self, block: Vec<T>) -> Result<(), InterleaverEncodeError> {
+
+        if block.len() != self.k {
+            return Err(InterleaverEncodeError::InvalidBlockSize {
+                expected: self.k,
+                actual: block.len()
+            })
+        }
+
+        self.history.pop_front();
+        self.history.push_back(block);
+
+        Ok(())
+    }
+
+    /// Build the next type-4 block from the type-3 blocks in the history
+    fn interleave(&self) -> Vec<T> {
+
+        let mut interleaved = vec![T::default(); self.k];
+        let newest = self.n - 1;
+
+        for j in 1..self.k + 1 {
+            let (blocks_back, index) = over_n_blocks_source(self.k, self.n, j);
+            interleaved[j - 1] = self.history[newest - blocks_back][index - 1];
+        }
+
+        interleaved
+    }
+
+    /// Rebuild the oldest complete type-3 block from the type-4 blocks in the history
+    fn deinterleave(&self) -> Vec<T> {
+
+        let mut deinterleaved = vec![T::default(); self.k];
+
+        // The oldest type-4 block in the history carries the first part of the type-3 block
+        for j in 1..self.k + 1 {
+            let (blocks_back, index) = over_n_blocks_source(self.k, self.n, j);
+            deinterleaved[index - 1] = self.history[blocks_back][j - 1];
+        }
+
+        deinterleaved
+    }
+}
+
+impl OverNBlocksInterleaver {
+
+    /// Interleave the next type-3 block, producing the next type-4 block.
+    /// The produced block carries K/N bits from this and each of the previous N-1 type-3 blocks.
+    pub fn encode(&mut self, block: &Bits) -> Result<Bits, InterleaverEncodeError> {
+        self.push(block.iter().by_vals().collect())?;
+        Ok(self.interleave().into_iter().collect())
+    }
+
+    /// De-interleave the next type-4 block.
+    ///
+    /// Since a type-3 block is only complete once all N type-4 blocks carrying it have been
+    /// received, the returned block is the one sent N-1 blocks ago.
+    pub fn decode(&mut self, block: &Bits) -> Result<Bits, InterleaverEncodeError> {
+        self.push(block.iter().by_vals().collect())?;
+        Ok(self.deinterleave().into_iter().collect())
+    }
+}
+
+impl OverNBlocksInterleaver<f32> {
+
+    /// De-interleave the next block of soft bits, delayed by N-1 blocks as for [`Self::decode`].
+    /// The history is initially filled with erasures.
+    pub fn decode_soft(&mut self, block: &[f32]) -> Result<SoftBits, InterleaverEncodeError> {
+        self.push(block.to_vec())?;
+        Ok(self.deinterleave())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -75,6 +209,67 @@ mod tests {
         
         // Assert that the decoded block is the same as the input
         assert_eq!(input, decoded);
+
+        // Soft bits should follow the same permutation
+        let soft = crate::bits::to_soft_bits(&result);
+        let decoded_soft = super::interleaver_decode_soft(&soft, K, A);
+        assert_eq!(crate::bits::hard_decision(&decoded_soft), input);
         
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn it_interleaves_over_n_blocks() {
+
+        const K: usize = 432;
+
+        for n in [1, 4, 8] {
+
+            let mut encoder = super::OverNBlocksInterleaver::new(K, n).unwrap();
+            let mut decoder = super::OverNBlocksInterleaver::new(K, n).unwrap();
+
+            // Generate a sequence of distinct blocks
+            let blocks: Vec<_> = (0..12)
+                .map(|b| {
+                    let mut block = super::Bits::repeat(false, K);
+                    block.fill_with(|idx| (idx * 7 + b * 3) % 5 < 2);
+                    block
+                })
+                .collect();
+
+            for (index, block) in blocks.iter().enumerate() {
+                let interleaved = encoder.encode(block).unwrap();
+                let decoded = decoder.decode(&interleaved).unwrap();
+
+                // Output is delayed by N-1 blocks
+                if index + 1 >= n {
+                    assert_eq!(decoded, blocks[index + 1 - n]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_spreads_a_block_over_n_blocks() {
+
+        const K: usize = 432;
+        const N: usize = 8;
+
+        let mut encoder = super::OverNBlocksInterleaver::new(K, N).unwrap();
+
+        // A single all-1s block followed by zero blocks, should set exactly K/N bits in each of
+        // the next N type-4 blocks and nothing afterwards
+        let ones = super::Bits::repeat(true, K);
+        let zeros = super::Bits::repeat(false, K);
+
+        assert_eq!(encoder.encode(&ones).unwrap().count_ones(), K / N);
+        for _ in 1..N {
+            assert_eq!(encoder.encode(&zeros).unwrap().count_ones(), K / N);
+        }
+        assert_eq!(encoder.encode(&zeros).unwrap().count_ones(), 0);
+    }
+
+    #[test]
+    fn it_rejects_invalid_depth() {
+        assert!(super::OverNBlocksInterleaver::<bool>::new(432, 5).is_err());
+    }
+}
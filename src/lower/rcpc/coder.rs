@@ -1,8 +1,8 @@
 use bitvec::prelude::BitVec;
-use crate::bits::Bits;
+use crate::bits::{Bits, SoftBits};
 use crate::lower::rcpc::state::State;
 use crate::lower::rcpc::puncturers::{Puncturer};
-use crate::lower::rcpc::viterbi::{build_trellis, viterbi_decode};
+use crate::lower::rcpc::viterbi::{build_trellis, viterbi_decode, viterbi_decode_soft};
 
 macro_rules! bit {
     ($b:expr) => {
@@ -92,6 +92,25 @@ pub fn depuncture(punctured: &Bits, puncturer: &Puncturer) -> Depunctured {
     }
 }
 
+/// Depuncture a punctured block of soft bits, leaving the punctured positions as erasures
+pub fn depuncture_soft(punctured: &[f32], puncturer: &Puncturer) -> SoftBits {
+
+    let depunctured_len =
+        ((punctured.len() * puncturer.numerator as usize) / puncturer.denominator as usize) * 4;
+
+    let mut mother = vec![0.0; depunctured_len];
+
+    for in_index in 1..punctured.len() + 1 {
+        let i = (puncturer.i)(in_index);
+        let k = puncturer.period * ((i - 1) / puncturer.t) + puncturer.coefficients[
+            i - puncturer.t * ((i - 1) / puncturer.t)
+        ];
+        mother[k - 1] = punctured[in_index - 1];
+    }
+
+    mother
+}
+
 /// RCPC-encode a block using the specified optional puncturing
 pub fn rcpc_encode(block: &Bits, maybe_puncturer: Option<&Puncturer>) -> Bits {
 
@@ -128,11 +147,24 @@ pub fn rcpc_decode(block: &Bits, maybe_puncturer: Option<&Puncturer>) -> Bits {
     viterbi_decode(depunctured.mother, depunctured.valid_mask, &trellis)
 }
 
+/// RCPC-decode a block of soft bits using the specified optional depuncturing
+pub fn rcpc_decode_soft(block: &[f32], maybe_puncturer: Option<&Puncturer>) -> Bits {
+
+    let mother = match maybe_puncturer {
+        Some(puncturer) => depuncture_soft(block, puncturer),
+        None => block.to_vec()
+    };
+
+    // Viterbi-decode the block
+    let trellis = build_trellis();
+    viterbi_decode_soft(&mother, &trellis)
+}
+
 #[cfg(test)]
 mod tests {
 
     use crate::lower::rcpc::puncturers::PredefinedPuncturer::{Rate1Over3Puncturer, Rate2Over3Puncturer};
-    use crate::bits::from_bitstr;
+    use crate::bits::{from_bitstr, to_soft_bits};
     use super::*;
 
     /// Check that the mother code behaviour is consistent with osmo-tetra
@@ -175,6 +207,12 @@ mod tests {
             for valid_index in depunctured.valid_mask.iter_ones() {
                 assert_eq!(depunctured.mother[valid_index], mother_code[valid_index]);
             }
+
+            // Soft depuncturing should leave erasures exactly where the mask is clear
+            let depunctured_soft = depuncture_soft(&to_soft_bits(&punctured), &puncturer);
+            for (index, llr) in depunctured_soft.iter().enumerate() {
+                assert_eq!(*llr != 0.0, depunctured.valid_mask[index]);
+            }
         }
     }
 
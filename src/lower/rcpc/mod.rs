@@ -2,4 +2,4 @@ pub mod puncturers;
 mod coder;
 mod state;
 mod viterbi;
-pub use coder::{rcpc_encode, rcpc_decode};
\ No newline at end of file
+pub use coder::{rcpc_encode, rcpc_decode, rcpc_decode_soft};
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::bits::Bits;
+use crate::bits::{Bits, SoftBits};
 use crate::lower::rcpc::coder::encode_bit;
 use crate::lower::rcpc::state::State;
 
@@ -58,38 +58,42 @@ pub fn build_trellis() -> Vec<StateTransitions> {
         .collect()
 }
 
-/// Compute the branch metric for an input codeword and corresponding validity bits
-/// For invalid bits, a score of 0 is accumulated.
-/// For valid bits, a score of -1 is accumulated for matches or -1 for mismatches.
-fn branch_metric_value(input: [bool; 4], validity: [bool; 4], expected: [bool; 4]) -> i32 {
+/// Compute the branch metric for a received soft codeword against the expected codeword.
+/// Each bit contributes its log-likelihood ratio, negated when the expected bit is a 0, so that
+/// agreeing bits lower the cost in proportion to their reliability and erasures contribute nothing.
+fn branch_metric_value(input: &[f32], expected: [bool; 4]) -> f32 {
    input
        .iter()
-       .zip(validity.iter())
        .zip(expected.iter())
-       .map(|((&rx, &valid), &expected)| {
-
-           // If this bit is uncertain (I.e. not valid, the result of depuncturing), return 0
-           if !valid {
-               return 0;
-           }
-           
-           // Otherwise, return +1 for mismatches or -1 for matches
-           return if rx != expected {
-               1
-           } else {
-               -1
-           }
-
-       })
+       .map(|(&llr, &expected)| if expected { llr } else { -llr })
        .sum()
 }
 
-/// Decode a 1/4-rate convolutionally-coded message
-pub fn viterbi_decode(input: Bits, valid_mask: Bits, trellis: &Vec<StateTransitions>) -> Bits {
+/// Decode a 1/4-rate convolutionally-coded message from hard bits.
+/// Bits which are not set in the validity mask (I.e. the result of depuncturing) are treated as
+/// erasures.
+pub fn viterbi_decode(input: Bits, valid_mask: Bits, trellis: &[StateTransitions]) -> Bits {
 
     // validity mask and input must be the same length
     assert_eq!(input.len(), valid_mask.len());
 
+    // Each valid bit has unit reliability
+    let soft: SoftBits = input
+        .iter()
+        .zip(valid_mask.iter())
+        .map(|(bit, valid)| match (*valid, *bit) {
+            (false, _) => 0.0,
+            (true, false) => 1.0,
+            (true, true) => -1.0
+        })
+        .collect();
+
+    viterbi_decode_soft(&soft, trellis)
+}
+
+/// Decode a 1/4-rate convolutionally-coded message from soft bits
+pub fn viterbi_decode_soft(input: &[f32], trellis: &[StateTransitions]) -> Bits {
+
     // Number of steps must be multiple of 4, since this is as 1/4 rate code
     assert_eq!(input.len() % 4, 0);
     let num_steps = input.len() / 4;
@@ -97,37 +101,32 @@ pub fn viterbi_decode(input: Bits, valid_mask: Bits, trellis: &Vec<StateTransiti
     // Number of states
     let num_states = trellis.len();
 
-    // Track path metrics
-    let lowest = i32::MIN / 4;
-    let mut prev: [i32; 16] = [lowest; 16];
-    let mut current: [i32; 16] = [0; 16];
+    // Track path metrics, where every state other than the start is unreachable
+    let unreachable = f32::INFINITY;
+    let mut prev: [f32; 16] = [unreachable; 16];
+    let mut current: [f32; 16] = [0.0; 16];
 
     // Start in state 0 with cost 0
-    prev[0] = 0;
+    prev[0] = 0.0;
 
     let mut survivors = vec![[0usize; 16]; num_steps];
 
     // For each step, which is 4 bits...
-    for (step, (input_chunk, valid_chunk)) in
-        input.chunks(4).zip(valid_mask.chunks(4)).enumerate() {
+    for (step, input_chunk) in input.chunks(4).enumerate() {
 
         // For each state in the trellis
         for next_state in 0..num_states {
 
             // There will be two possible incoming routes
             let incoming = &trellis[next_state];
-            let mut best_metric = lowest;
-            let mut best_prev_state = 0usize;
+            let mut best_metric = unreachable;
+            let mut best_prev_state = incoming[0].prev_state;
 
             // For each of the two transitions
             for trans in incoming {
 
                 // Calculate the branch cost
-                let branch_cost = branch_metric_value(
-                    [input_chunk[0], input_chunk[1], input_chunk[2], input_chunk[3]],
-                    [valid_chunk[0], valid_chunk[1], valid_chunk[2], valid_chunk[3]],
-                    trans.output_bits
-                );
+                let branch_cost = branch_metric_value(input_chunk, trans.output_bits);
 
                 // Accumulate the cost
                 let total_cost = prev[trans.prev_state] + branch_cost;
@@ -158,7 +157,7 @@ pub fn viterbi_decode(input: Bits, valid_mask: Bits, trellis: &Vec<StateTransiti
     let (mut state, _) = prev
         .iter()
         .enumerate()
-        .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .min_by(|a, b| a.1.total_cmp(b.1))
         .unwrap();
 
     // Work through the steps backwards
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use bitvec::prelude::*;
 use crate::bits::Bits;
 use crate::bits::from_bitstr;
 
@@ -85,11 +88,104 @@ pub enum ReedMullerDecodeError {
         expected: usize,
         actual: usize,
     },
-    MismatchedValue
+    Uncorrectable
 }
 
-/// Decode a 30-bit block using the Reed-Muller coder.
-pub fn rm_decode(block: &Bits) -> Result<Bits, ReedMullerDecodeError> {
+/// Number of bit errors the shortened (30,14) code can correct, given its minimum distance of 6
+const CORRECTABLE_ERRORS: usize = 2;
+
+/// A successfully decoded block
+#[derive(Debug)]
+pub struct ReedMullerDecoded {
+    pub payload: Bits,
+    pub corrected_bits: usize
+}
+
+/// Compute the 16-bit syndrome of a 30-bit block, which is zero for a valid codeword
+fn syndrome(block: &BitSlice<u8, Msb0>) -> u16 {
+
+    let mut parity = block[14..30].to_bitvec();
+
+    for (index, row) in generator_matrix_rows().iter().enumerate() {
+        if block[index] {
+            parity ^= row;
+        }
+    }
+
+    parity.load_be()
+}
+
+/// Map the syndrome of every correctable error pattern (up to two bits) to that pattern
+fn correctable_errors() -> &'static HashMap<u16, Bits> {
+
+    static ERRORS: OnceLock<HashMap<u16, Bits>> = OnceLock::new();
+
+    ERRORS.get_or_init(|| {
+
+        let mut errors = HashMap::new();
+        errors.insert(0, Bits::repeat(false, 30));
+
+        for first in 0..30 {
+            for second in first..30 {
+                let mut error = Bits::repeat(false, 30);
+                error.set(first, true);
+                error.set(second, true);
+                errors.insert(syndrome(&error), error);
+            }
+        }
+
+        errors
+    })
+}
+
+/// Every codeword packed MSB-first into the low 30 bits, indexed by the 14-bit payload it encodes
+fn codebook() -> &'static Vec<u32> {
+
+    static CODEBOOK: OnceLock<Vec<u32>> = OnceLock::new();
+
+    CODEBOOK.get_or_init(|| {
+
+        let rows: Vec<u32> = generate_ident_matrix().iter().map(|row| row.load_be()).collect();
+
+        (0..1usize << 14)
+            .map(|payload| {
+                rows.iter()
+                    .enumerate()
+                    .filter(|(index, _)| payload & (1 << (13 - index)) != 0)
+                    .fold(0, |codeword, (_, row)| codeword ^ row)
+            })
+            .collect()
+    })
+}
+
+/// Decode a 30-bit block using the Reed-Muller coder, correcting up to two bit errors.
+pub fn rm_decode(block: &Bits) -> Result<ReedMullerDecoded, ReedMullerDecodeError> {
+
+    if block.len() != 30 {
+        return Err(ReedMullerDecodeError::InvalidBlockSize {
+            expected: 30,
+            actual: block.len()
+        });
+    }
+
+    // Look up the error pattern which would give this syndrome
+    let error = correctable_errors()
+        .get(&syndrome(block))
+        .ok_or(ReedMullerDecodeError::Uncorrectable)?;
+
+    let corrected = block.clone() ^ error;
+
+    Ok(ReedMullerDecoded {
+        payload: corrected[0..14].to_bitvec(),
+        corrected_bits: error.count_ones()
+    })
+}
+
+/// Decode a 30-bit block of soft bits using the Reed-Muller coder.
+///
+/// The most likely codeword is found by correlating against every codeword. It is accepted if it
+/// differs from no more than two of the bits which were not erased.
+pub fn rm_decode_soft(block: &[f32]) -> Result<ReedMullerDecoded, ReedMullerDecodeError> {
 
     if block.len() != 30 {
         return Err(ReedMullerDecodeError::InvalidBlockSize {
@@ -98,19 +194,41 @@ pub fn rm_decode(block: &Bits) -> Result<Bits, ReedMullerDecodeError> {
         });
     }
 
-    // Extract the payload and code
-    let payload = block[0..14].to_bitvec();
-    let code = block[14..30].to_bitvec();
+    let codeword_bit = |codeword: u32, index: usize| codeword & (1 << (29 - index)) != 0;
+
+    let correlation = |codeword: u32| -> f32 {
+        block
+            .iter()
+            .enumerate()
+            .map(|(index, llr)| if codeword_bit(codeword, index) { -llr } else { *llr })
+            .sum()
+    };
+
+    let (payload, best, _) = codebook()
+        .iter()
+        .enumerate()
+        .map(|(payload, codeword)| (payload, codeword, correlation(*codeword)))
+        .max_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+        .unwrap();
 
-    // Validate by re-encoding
-    let reencoded = rm_encode(&payload).unwrap();
+    // Count the bits which were received with some confidence but disagree with the codeword
+    let corrected_bits = block
+        .iter()
+        .enumerate()
+        .filter(|(index, llr)| **llr != 0.0 && (**llr < 0.0) != codeword_bit(*best, *index))
+        .count();
 
-    // If the re-encoded block does not match the input block, return an error
-    if code.eq(&reencoded) {
-        return Err(ReedMullerDecodeError::MismatchedValue);
+    if corrected_bits > CORRECTABLE_ERRORS {
+        return Err(ReedMullerDecodeError::Uncorrectable);
     }
 
-    Ok(payload)
+    let mut decoded = Bits::repeat(false, 14);
+    decoded.store_be(payload);
+
+    Ok(ReedMullerDecoded {
+        payload: decoded,
+        corrected_bits
+    })
 }
 
 #[cfg(test)]
@@ -128,7 +246,60 @@ mod tests {
     fn it_encodes_and_decodes_correctly() {
         let block = from_bitstr("01010101010101");
         let encoded = super::rm_encode(&block).unwrap();
-        super::rm_decode(&encoded).unwrap();
+        let decoded = super::rm_decode(&encoded).unwrap();
+        assert_eq!(decoded.payload, block);
+        assert_eq!(decoded.corrected_bits, 0);
+    }
+
+    #[test]
+    fn it_corrects_up_to_two_errors() {
+
+        // Every pattern of up to two errors should have a distinct syndrome
+        assert_eq!(super::correctable_errors().len(), 1 + 30 + (30 * 29) / 2);
+
+        let block = from_bitstr("11001010011101");
+        let encoded = super::rm_encode(&block).unwrap();
+
+        for first in 0..30 {
+            for second in first + 1..30 {
+                let mut received = encoded.clone();
+                received.set(first, !encoded[first]);
+                received.set(second, !encoded[second]);
+
+                let decoded = super::rm_decode(&received).unwrap();
+                assert_eq!(decoded.payload, block);
+                assert_eq!(decoded.corrected_bits, 2);
+
+                // A third error is detected, but can't be corrected
+                let third = (second + 1) % 30;
+                if third != first {
+                    received.set(third, !encoded[third]);
+                    assert!(super::rm_decode(&received).is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_decodes_soft_bits_with_erasures() {
+
+        let block = from_bitstr("00110111000110");
+        let encoded = super::rm_encode(&block).unwrap();
+
+        // Erase five bits and flip two more, with low confidence
+        let soft: Vec<f32> = encoded
+            .iter()
+            .enumerate()
+            .map(|(index, bit)| match index {
+                0..5 => 0.0,
+                10 | 20 => if *bit { 0.5 } else { -0.5 },
+                _ => if *bit { -2.0 } else { 2.0 }
+            })
+            .collect();
+
+        let decoded = super::rm_decode_soft(&soft).unwrap();
+        assert_eq!(decoded.payload, block);
+        assert_eq!(decoded.corrected_bits, 2);
     }
 
     #[test]
@@ -1,6 +1,6 @@
 use std::fmt::{Display, Formatter};
 use bitvec::prelude::*;
-use crate::bits::Bits;
+use crate::bits::{Bits, SoftBits};
 
 #[derive(Clone)]
 pub(crate) struct State {
@@ -97,6 +97,17 @@ pub fn scrambler_decode(block: &Bits, initial_state: &State) -> Bits {
 
 }
 
+/// Descramble soft bits, inverting the sign of each bit which the scrambler would have flipped
+pub fn scrambler_decode_soft(block: &[f32], initial_state: &State) -> SoftBits {
+
+    let mut scrambler_state = initial_state.clone();
+
+    block
+        .iter()
+        .map(|llr| if lfsr_bit(&mut scrambler_state) { -llr } else { *llr })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
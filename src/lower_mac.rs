@@ -1,43 +1,72 @@
+use crate::bits::Bits;
 use crate::bits_to_bin;
-use crate::burst::{NormalContDownlinkBurst, SyncContDownlinkBurst, DownlinkBurst, Build};
-use crate::logical_channels::LogicalChannel;
+use crate::burst::{
+    NormalContDownlinkBurst,
+    SyncContDownlinkBurst,
+    DownlinkBurst,
+    LinearisationBurst,
+    UplinkBurst,
+    LINEARISATION_BITS
+};
+use crate::codec::{Decodable, DecodeError, Reader};
+use crate::logical_channels::{LogicalChannel, LogicalChannelDecodeError};
+use crate::lower::scrambler::State;
+use crate::pdu::downlink::{
+    AccessAssign,
+    AccessAssignControlFrame,
+    BaseFrameLength,
+    DownlinkMACPDU,
+    Length,
+    MACResourcePDU,
+    MLESyncPDU,
+    SharingMode,
+    Sync
+};
 use crate::tdma_time::TDMATime;
-use crate::upper_mac::UpperMAC;
+use crate::upper_mac::{TMVUnitData, UpperMAC};
 
 /// Generate a downlink slot for the provided time.
 /// Requests MAC blocks from the upper MAC and maps them onto a burst ready for the physical layer
-pub(crate) fn generate_dl_slot(time: &TDMATime) -> Box<dyn Build> {
-
-    // TODO: this will be passed-in?
-    let mac = UpperMAC::new();
+pub(crate) fn generate_dl_slot(mac: &mut UpperMAC, time: &TDMATime) -> DownlinkBurst {
 
     // Request the block(s)
     let blocks = mac.generate_slot(time);
 
     let aach_raw = blocks.aach.clone();
     eprintln!("AACH {}", bits_to_bin!(aach_raw));
-    eprintln!("AACH Enc {}", bits_to_bin!(LogicalChannel::AccessAssignment.encode(aach_raw, &blocks.primary.scrambling_code)));
+    eprintln!("AACH Enc {}", bits_to_bin!(encode_channel(&LogicalChannel::AccessAssignment, aach_raw, &blocks.primary.scrambling_code)));
+
+    map_dl_slot(blocks, &mac.config().sharing_mode)
+}
 
+/// Map the MAC block(s) provided by the upper MAC onto a downlink burst.
+/// Discontinuous bursts are used unless the carrier is continuously transmitted.
+pub(crate) fn map_dl_slot(blocks: TMVUnitData, sharing_mode: &SharingMode) -> DownlinkBurst {
 
     // Burst type shall be based on the channel of the primary block
-    match blocks.primary.logical_channel {
+    let burst = match blocks.primary.logical_channel {
 
         // BSCH + (SCH/HD or BNCH) => SB
         LogicalChannel::BroadcastSynchronisation => {
 
-            Box::new(SyncContDownlinkBurst {
-                sb1_bits: blocks.primary.logical_channel.encode(
+            let secondary = match blocks.secondary {
+                Some(block) => block,
+                None => panic!("BSCH provided without SB2 content")
+            };
+
+            // Only the BSCH uses the zero scrambling code, the AACH is scrambled as for SB2
+            DownlinkBurst::Sync(SyncContDownlinkBurst {
+                sb1_bits: encode_channel(
+                    &blocks.primary.logical_channel,
                     blocks.primary.mac_block,
                     &blocks.primary.scrambling_code
                 ),
-                sb2_bits: match blocks.secondary {
-                    Some(block) => block.logical_channel.encode(
-                        block.mac_block,
-                        &block.scrambling_code
-                    ),
-                    None => panic!("BSCH provided without SB2 content")
-                },
-                bb_bits: (LogicalChannel::AccessAssignment).encode(blocks.aach, &blocks.primary.scrambling_code),
+                bb_bits: encode_channel(&LogicalChannel::AccessAssignment, blocks.aach, &secondary.scrambling_code),
+                sb2_bits: encode_channel(
+                    &secondary.logical_channel,
+                    secondary.mac_block,
+                    &secondary.scrambling_code
+                ),
             })
 
         }
@@ -45,19 +74,21 @@ pub(crate) fn generate_dl_slot(time: &TDMATime) -> Box<dyn Build> {
         // SCH/HD + SCH/HD => NDB
         LogicalChannel::SignallingHalfDownlink => {
 
-            Box::new(NormalContDownlinkBurst {
-                bkn1_bits: blocks.primary.logical_channel.encode(
+            DownlinkBurst::Normal(NormalContDownlinkBurst {
+                bkn1_bits: encode_channel(
+                    &blocks.primary.logical_channel,
                     blocks.primary.mac_block,
                     &blocks.primary.scrambling_code
                 ),
                 bkn2_bits: match blocks.secondary {
-                    Some(block) => block.logical_channel.encode(
+                    Some(block) => encode_channel(
+                        &block.logical_channel,
                         block.mac_block,
                         &block.scrambling_code
                     ),
                     None => panic!("BSCH provided without SB2 content")
                 },
-                bb_bits: (LogicalChannel::AccessAssignment).encode(blocks.aach, &blocks.primary.scrambling_code),
+                bb_bits: encode_channel(&LogicalChannel::AccessAssignment, blocks.aach, &blocks.primary.scrambling_code),
                 slot_flag: false
             })
 
@@ -67,26 +98,50 @@ pub(crate) fn generate_dl_slot(time: &TDMATime) -> Box<dyn Build> {
         LogicalChannel::BroadcastNetwork => {
 
             // BNCH is always mapped to bkn2, so send the secondary block in bkn1
-            Box::new(NormalContDownlinkBurst {
+            DownlinkBurst::Normal(NormalContDownlinkBurst {
                 bkn1_bits: match blocks.secondary {
-                    Some(block) => block.logical_channel.encode(
+                    Some(block) => encode_channel(
+                        &block.logical_channel,
                         block.mac_block,
                         &block.scrambling_code
                     ),
                     None => panic!("BNCH provided without SB1 content")
                 },
-                bkn2_bits: blocks.primary.logical_channel.encode(
+                bkn2_bits: encode_channel(
+                    &blocks.primary.logical_channel,
                     blocks.primary.mac_block,
                     &blocks.primary.scrambling_code
                 ),
-                bb_bits: (LogicalChannel::AccessAssignment).encode(blocks.aach, &blocks.primary.scrambling_code),
+                bb_bits: encode_channel(&LogicalChannel::AccessAssignment, blocks.aach, &blocks.primary.scrambling_code),
                 slot_flag: false
             })
 
         },
 
-        // STCH + SCH/HD => NDB + SF
-        LogicalChannel::Stealing => { todo!("downlink stealing not implemented") }
+        // STCH + (STCH or TCH) => NDB + SF
+        LogicalChannel::Stealing => {
+
+            // The first half-slot is always the stolen one, the second half is either also stolen
+            // or continues to carry traffic
+            DownlinkBurst::Normal(NormalContDownlinkBurst {
+                bkn1_bits: encode_channel(
+                    &blocks.primary.logical_channel,
+                    blocks.primary.mac_block,
+                    &blocks.primary.scrambling_code
+                ),
+                bkn2_bits: match blocks.secondary {
+                    Some(block) => encode_channel(
+                        &block.logical_channel,
+                        block.mac_block,
+                        &block.scrambling_code
+                    ),
+                    None => panic!("STCH provided without second half-slot content")
+                },
+                bb_bits: encode_channel(&LogicalChannel::AccessAssignment, blocks.aach, &blocks.primary.scrambling_code),
+                slot_flag: true
+            })
+
+        }
 
         // SCH/F => NDB
         LogicalChannel::SignallingFull => {
@@ -95,22 +150,484 @@ pub(crate) fn generate_dl_slot(time: &TDMATime) -> Box<dyn Build> {
             assert!(blocks.secondary.is_none(), "Multiplexing requested but SCH/F provided");
 
             // Split the channel bits between the two burst blocks
-            let burst_bits = blocks.primary.logical_channel.encode(
+            let burst_bits = encode_channel(
+                &blocks.primary.logical_channel,
                 blocks.primary.mac_block,
                 &blocks.primary.scrambling_code
             );
 
-            Box::new(NormalContDownlinkBurst {
+            DownlinkBurst::Normal(NormalContDownlinkBurst {
                 bkn1_bits: burst_bits[..216].to_bitvec(),
                 bkn2_bits: burst_bits[216..].to_bitvec(),
-                bb_bits: Default::default(),
+                bb_bits: encode_channel(&LogicalChannel::AccessAssignment, blocks.aach, &blocks.primary.scrambling_code),
                 slot_flag: false
             })
 
         }
         _ => panic!("invalid primary block type")
+    };
+
+    match sharing_mode {
+        SharingMode::ContinuousTransmission => burst,
+        _ => burst.into_discontinuous()
+    }
+}
+
+/// Generate the uplink linearisation burst for the provided time, if the AACH has reserved the
+/// first subslot for the CLCH. This is only possible during the control frame (frame 18).
+pub(crate) fn generate_ul_clch(time: &TDMATime, access_assign: &AccessAssign) -> Option<UplinkBurst> {
+
+    if !time.is_control_frame() {
+        return None;
+    }
+
+    // The first access field describes the first subslot
+    let access_field_1 = match access_assign {
+        AccessAssign::ControlFrame(
+            AccessAssignControlFrame::UplinkCommonOnly { access_field_1, .. } |
+            AccessAssignControlFrame::UplinkCommonAndAssigned { access_field_1, .. } |
+            AccessAssignControlFrame::UplinkAssignedOnly { access_field_1, .. }
+        ) => access_field_1,
+        _ => return None
+    };
+
+    if !matches!(access_field_1.base_frame_length, BaseFrameLength::CLCHSubslot) {
+        return None;
+    }
+
+    // The linearisation content is arbitrary, the CLCH applies no coding
+    let clch_bits = encode_channel(
+        &LogicalChannel::CommonLinearisation,
+        Bits::repeat(false, LINEARISATION_BITS),
+        &State::zero()
+    );
+
+    Some(UplinkBurst::Linearisation(LinearisationBurst {
+        linearisation_bits: clch_bits
+    }))
+}
+
+/// The content of a normal downlink burst received on a traffic channel
+pub(crate) struct ReceivedTrafficSlot {
+    /// Decoded STCH MAC blocks, in the order in which they were received
+    pub(crate) stch_blocks: Vec<Bits>,
+    /// Undecoded (type-5) traffic bits; the whole slot if nothing was stolen, or the second
+    /// half-slot if only the first half was stolen
+    pub(crate) traffic_bits: Option<Bits>
+}
+
+/// Route the blocks of a normal downlink burst received on a traffic channel.
+///
+/// If the slot flag is clear the whole slot carries traffic. Otherwise the first half-slot is
+/// STCH and the second half-slot is also STCH if the first half's MAC-RESOURCE indicates that
+/// the second half-slot is stolen (EN 300 392-2 § 23.8.4.2.1), or traffic if not.
+pub(crate) fn receive_traffic_slot(
+    burst: &NormalContDownlinkBurst,
+    scrambling_code: &State
+) -> Result<ReceivedTrafficSlot, LogicalChannelDecodeError> {
+
+    // No stealing, the whole slot is traffic
+    if !burst.slot_flag {
+        let mut traffic_bits = burst.bkn1_bits.clone();
+        traffic_bits.extend_from_bitslice(&burst.bkn2_bits);
+        return Ok(ReceivedTrafficSlot {
+            stch_blocks: vec![],
+            traffic_bits: Some(traffic_bits)
+        })
+    }
+
+    // First half-slot is always stolen
+    let first_half = LogicalChannel::Stealing.decode(burst.bkn1_bits.clone(), scrambling_code)?;
+
+    if second_half_stolen(&first_half) {
+        let second_half = LogicalChannel::Stealing.decode(burst.bkn2_bits.clone(), scrambling_code)?;
+        Ok(ReceivedTrafficSlot {
+            stch_blocks: vec![first_half, second_half],
+            traffic_bits: None
+        })
+    } else {
+        Ok(ReceivedTrafficSlot {
+            stch_blocks: vec![first_half],
+            traffic_bits: Some(burst.bkn2_bits.clone())
+        })
+    }
+}
+
+/// Determine whether the first STCH half-slot indicates that the second half-slot is stolen too
+fn second_half_stolen(first_half: &Bits) -> bool {
+
+    // Only MAC-RESOURCE may carry the indication
+    let mut reader = Reader::new(first_half);
+    match MACResourcePDU::decode(&mut reader) {
+        Ok(pdu) => pdu.length == Length::SecondHalfSlotStolen,
+        Err(_) => false
+    }
+}
+
+/// The content of a downlink slot, as decoded by the receiver
+#[derive(Debug)]
+pub(crate) struct ReceivedDownlinkSlot {
+    /// The SYNC PDU and the MLE PDU it carries, if the slot contained the BSCH
+    pub(crate) sync: Option<(Sync, MLESyncPDU)>,
+    /// The ACCESS-ASSIGN PDU from the AACH, if it was received correctly
+    pub(crate) access_assign: Option<AccessAssign>,
+    /// The PDUs associated in each MAC block which was received correctly
+    pub(crate) pdus: Vec<DownlinkMACPDU>,
+    /// Undecoded (type-5) traffic bits, if the slot was found to carry traffic
+    pub(crate) traffic_bits: Option<Bits>
+}
+
+/// Receives downlink slots, the mirror of `generate_dl_slot`.
+///
+/// Everything but the BSCH is scrambled using the identity of the cell, which the receiver learns
+/// from the BSCH. Until a synchronisation burst has been received, normal bursts can't be decoded.
+/// The BSCH also gives the time of the slot, which is then tracked to parse the AACH, so bursts
+/// must be provided for consecutive slots, skipping any which weren't received.
+pub(crate) struct DownlinkReceiver {
+    scrambling_code: Option<State>,
+    time: Option<TDMATime>
+}
+
+impl DownlinkReceiver {
+
+    pub(crate) fn new() -> Self {
+        DownlinkReceiver {
+            scrambling_code: None,
+            time: None
+        }
+    }
+
+    /// The scrambling code of the cell, if it has been learnt from the BSCH
+    pub(crate) fn scrambling_code(&self) -> Option<&State> {
+        self.scrambling_code.as_ref()
+    }
+
+    /// The time of the last slot received, if it has been learnt from the BSCH. The hyperframe
+    /// number isn't sent in the BSCH, so is always 1.
+    pub(crate) fn time(&self) -> Option<&TDMATime> {
+        self.time.as_ref()
+    }
+
+    /// Account for slots which weren't received, such as when the synchroniser misses bursts
+    pub(crate) fn skip_slots(&mut self, count: usize) {
+        for _ in 0..count {
+            self.time = self.time.take().map(TDMATime::next);
+        }
+    }
+
+    /// Decode the logical channels carried by the downlink burst of the next slot and parse the
+    /// PDUs within them. Returns None if the burst couldn't be decoded at all.
+    pub(crate) fn receive_dl_slot(&mut self, burst: &DownlinkBurst) -> Option<ReceivedDownlinkSlot> {
+
+        self.skip_slots(1);
+
+        match burst {
+            DownlinkBurst::Sync(burst) => self.receive_sync_burst(burst),
+            DownlinkBurst::Normal(burst) => self.receive_normal_burst(burst),
+            DownlinkBurst::SyncDiscontinuous(burst) => self.receive_sync_burst(&burst.into()),
+            DownlinkBurst::NormalDiscontinuous(burst) => self.receive_normal_burst(&burst.into()),
+            DownlinkBurst::Linearisation(_) => {
+                log::debug!("linearisation burst carries no logical channels");
+                None
+            }
+        }
+    }
+
+    /// Decode and parse the ACCESS-ASSIGN PDU from the AACH of the current slot
+    fn receive_aach(&self, bb_bits: &Bits, scrambling_code: &State) -> Option<AccessAssign> {
+
+        let aach_bits = decode_block(&LogicalChannel::AccessAssignment, bb_bits, scrambling_code)?;
+        let time = self.time.as_ref()?;
+
+        match AccessAssign::decode(&mut Reader::new(&aach_bits), time) {
+            Ok(access_assign) => Some(access_assign),
+            Err(err) => {
+                log::warn!("failed to decode ACCESS-ASSIGN: {err:?}");
+                None
+            }
+        }
+    }
+
+    /// BSCH + AACH + (SCH/HD or BNCH)
+    fn receive_sync_burst(&mut self, burst: &SyncContDownlinkBurst) -> Option<ReceivedDownlinkSlot> {
+
+        // The BSCH is always scrambled with the zero code
+        let bsch_bits = decode_block(&LogicalChannel::BroadcastSynchronisation, &burst.sb1_bits, &State::zero())?;
+
+        let (sync, mle_sync) = match decode_sync(&bsch_bits) {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                log::warn!("failed to decode SYNC: {err:?}");
+                return None;
+            }
+        };
+
+        // Derive the scrambling code for everything else
+        let scrambling_code = State::new(mle_sync.mcc, mle_sync.mnc, sync.colour_code);
+
+        // The timeslot number is coded 0-3, the others from 1
+        self.time = Some(TDMATime::at(
+            sync.timeslot_number,
+            sync.frame_number.saturating_sub(1),
+            sync.multiframe_number.saturating_sub(1),
+            0
+        ));
+
+        // SCH/HD and BNCH are coded identically, the PDU type tells them apart
+        let pdus = decode_block(&LogicalChannel::SignallingHalfDownlink, &burst.sb2_bits, &scrambling_code)
+            .map(|block| parse_block(&block))
+            .unwrap_or_default();
+
+        let slot = ReceivedDownlinkSlot {
+            access_assign: self.receive_aach(&burst.bb_bits, &scrambling_code),
+            sync: Some((sync, mle_sync)),
+            pdus,
+            traffic_bits: None
+        };
+
+        self.scrambling_code = Some(scrambling_code);
+
+        Some(slot)
+    }
+
+    /// AACH + (SCH/HD or BNCH or STCH) + (SCH/HD or BNCH or STCH), or AACH + SCH/F, or AACH + TCH
+    fn receive_normal_burst(&mut self, burst: &NormalContDownlinkBurst) -> Option<ReceivedDownlinkSlot> {
+
+        let Some(scrambling_code) = &self.scrambling_code else {
+            log::debug!("scrambling code not known yet, ignoring normal burst");
+            return None;
+        };
+
+        let access_assign = self.receive_aach(&burst.bb_bits, scrambling_code);
+
+        // Stolen from a traffic channel
+        if burst.slot_flag {
+            return match receive_traffic_slot(burst, scrambling_code) {
+                Ok(received) => Some(ReceivedDownlinkSlot {
+                    sync: None,
+                    access_assign,
+                    pdus: received.stch_blocks.iter().flat_map(parse_block).collect(),
+                    traffic_bits: received.traffic_bits
+                }),
+                Err(err) => {
+                    log::warn!("failed to decode STCH: {err:?}");
+                    None
+                }
+            }
+        }
+
+        // Two half-slots?
+        let half_slots: Vec<Bits> = [&burst.bkn1_bits, &burst.bkn2_bits]
+            .into_iter()
+            .filter_map(|bits| {
+                LogicalChannel::SignallingHalfDownlink.decode(bits.clone(), scrambling_code).ok()
+            })
+            .collect();
+
+        let mut slot = ReceivedDownlinkSlot {
+            sync: None,
+            access_assign,
+            pdus: half_slots.iter().flat_map(parse_block).collect(),
+            traffic_bits: None
+        };
+
+        if !slot.pdus.is_empty() {
+            return Some(slot);
+        }
+
+        // Otherwise a full slot, either signalling or traffic
+        let mut full_slot_bits = burst.bkn1_bits.clone();
+        full_slot_bits.extend_from_bitslice(&burst.bkn2_bits);
+
+        match LogicalChannel::SignallingFull.decode(full_slot_bits.clone(), scrambling_code) {
+            Ok(block) => slot.pdus.extend(parse_block(&block)),
+            Err(_) => slot.traffic_bits = Some(full_slot_bits)
+        }
+
+        Some(slot)
+    }
+}
+
+/// Decode the SYNC PDU and the MLE PDU it carries from the BSCH
+fn decode_sync(bsch_bits: &Bits) -> Result<(Sync, MLESyncPDU), DecodeError> {
+    let sync = Sync::decode(&mut Reader::new(bsch_bits))?;
+    let mle_sync = MLESyncPDU::decode(&mut Reader::new(&sync.tm_sdu_bits))?;
+    Ok((sync, mle_sync))
+}
+
+/// Parse the PDUs associated in a MAC block, logging rather than returning the failure
+fn parse_block(block: &Bits) -> Vec<DownlinkMACPDU> {
+    match DownlinkMACPDU::dissociate(block) {
+        Ok(pdus) => pdus,
+        Err(err) => {
+            log::warn!("failed to parse MAC block: {err:?}");
+            vec![]
+        }
+    }
+}
+
+/// Encode a block onto its logical channel. MAC blocks are never mapped onto traffic channels
+/// interleaved over N blocks, so no interleaver state is needed.
+fn encode_channel(logical_channel: &LogicalChannel, type1_bits: Bits, scrambling_code: &State) -> Bits {
+    logical_channel.encode(type1_bits, scrambling_code)
+        .expect("MAC blocks are not mapped onto channels interleaved over N blocks")
+}
+
+/// Decode a single block, logging rather than returning the failure
+fn decode_block(logical_channel: &LogicalChannel, bits: &Bits, scrambling_code: &State) -> Option<Bits> {
+    match logical_channel.decode(bits.clone(), scrambling_code) {
+        Ok(block) => Some(block),
+        Err(err) => {
+            log::warn!("failed to decode block: {err:?}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::burst::{Build, Extract, Synchroniser};
+    use crate::cell_config::CellConfig;
+    use crate::codec::{add_fill_bits, Encodable, FillBitCapacity, Writer};
+    use crate::upper_mac::SecondHalfSlot;
+
+    fn stch_block(length: Length) -> Bits {
+        let pdu = MACResourcePDU {
+            length,
+            ..MACResourcePDU::null()
+        };
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let mut bits = writer.done();
+        add_fill_bits(&mut bits, FillBitCapacity::Bits(124));
+        bits
     }
 
+    #[test]
+    fn steals_first_half_slot() {
+
+        let mac = UpperMAC::new(CellConfig::default());
+        let time = TDMATime::at(1, 3, 0, 0);
+        let scrambling_code = mac.scrambling_code();
+
+        let first_half = stch_block(Length::NullPDU);
+        let traffic = Bits::repeat(true, 216);
+        let blocks = mac.generate_stolen_slot(&time, first_half.clone(), SecondHalfSlot::Traffic(traffic));
+
+        let burst = NormalContDownlinkBurst::extract(map_dl_slot(blocks, &SharingMode::ContinuousTransmission).build()).unwrap();
+        assert!(burst.slot_flag);
+
+        let received = receive_traffic_slot(&burst, &scrambling_code).unwrap();
+        assert_eq!(received.stch_blocks, vec![first_half]);
+
+        // Traffic is only scrambled
+        assert_eq!(
+            LogicalChannel::TrafficUnprotected.decode(received.traffic_bits.unwrap(), &scrambling_code).unwrap(),
+            Bits::repeat(true, 216)
+        );
+    }
+
+    #[test]
+    fn steals_both_half_slots() {
+
+        let mac = UpperMAC::new(CellConfig::default());
+        let time = TDMATime::at(1, 3, 0, 0);
+        let scrambling_code = mac.scrambling_code();
 
+        let first_half = stch_block(Length::SecondHalfSlotStolen);
+        let second_half = stch_block(Length::NullPDU);
+        let blocks = mac.generate_stolen_slot(
+            &time,
+            first_half.clone(),
+            SecondHalfSlot::Stolen(second_half.clone())
+        );
+
+        let burst = NormalContDownlinkBurst::extract(map_dl_slot(blocks, &SharingMode::ContinuousTransmission).build()).unwrap();
+        let received = receive_traffic_slot(&burst, &scrambling_code).unwrap();
+        assert_eq!(received.stch_blocks, vec![first_half, second_half]);
+        assert!(received.traffic_bits.is_none());
+    }
+
+    #[test]
+    fn generates_clch_when_granted() {
+
+        let mac = UpperMAC::new(CellConfig::default());
+
+        // Granted in the control frame where (MN + TN) % 4 = 3
+        let time = TDMATime::at(1, 17, 0, 0);
+        let access_assign = mac.generate_access_assign(&time);
+        let burst = generate_ul_clch(&time, &access_assign).unwrap();
+        assert!(matches!(burst, UplinkBurst::Linearisation(_)));
+        assert_eq!(burst.build().len(), 255);
+
+        // Not granted in the other control frame slots
+        let time = TDMATime::at(0, 17, 0, 0);
+        let access_assign = mac.generate_access_assign(&time);
+        assert!(generate_ul_clch(&time, &access_assign).is_none());
+
+        // Never outside of the control frame
+        let time = TDMATime::at(1, 3, 0, 0);
+        let access_assign = mac.generate_access_assign(&time);
+        assert!(generate_ul_clch(&time, &access_assign).is_none());
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn receives_generated_slots() {
+
+        let mut mac = UpperMAC::new(CellConfig::default());
+
+        // Frame 18 of multiframe 1, timeslots 1 to 4
+        let times: Vec<TDMATime> = (0..4).map(|slot| TDMATime::at(slot, 17, 0, 0)).collect();
+
+        let mut stream = Bits::repeat(false, 100);
+        for time in &times {
+            stream.extend(generate_dl_slot(&mut mac, time).build());
+        }
+
+        let bursts = Synchroniser::new(0).feed(stream);
+        assert_eq!(bursts.len(), 4);
+
+        let mut receiver = DownlinkReceiver::new();
+
+        // Nothing can be decoded until the BSCH has been received
+        assert!(receiver.receive_dl_slot(&bursts[0].burst).is_none());
+
+        // BSCH in timeslot 2, with SCH/HD in SB2
+        let slot = receiver.receive_dl_slot(&bursts[1].burst).unwrap();
+        let (sync, mle_sync) = slot.sync.unwrap();
+        assert_eq!(sync.timeslot_number, 1);
+        assert_eq!(mle_sync.mcc, 234);
+        assert_eq!(receiver.scrambling_code().unwrap().state, mac.scrambling_code().state);
+        assert_eq!(receiver.time(), Some(&times[1]));
+        assert_eq!(slot.access_assign, Some(mac.generate_access_assign(&times[1])));
+        assert!(matches!(slot.pdus[..], [DownlinkMACPDU::MACResource(_)]));
+
+        // Two SCH/HD
+        let slot = receiver.receive_dl_slot(&bursts[2].burst).unwrap();
+        assert!(matches!(slot.pdus[..], [DownlinkMACPDU::MACResource(_), DownlinkMACPDU::MACResource(_)]));
+
+        // SCH/HD + BNCH in timeslot 4
+        let slot = receiver.receive_dl_slot(&bursts[3].burst).unwrap();
+        assert!(matches!(slot.pdus[..], [DownlinkMACPDU::MACResource(_), DownlinkMACPDU::Sysinfo(_)]));
+        assert!(slot.traffic_bits.is_none());
+        assert_eq!(slot.access_assign, Some(mac.generate_access_assign(&times[3])));
+    }
+
+    #[test]
+    fn receives_discontinuous_slots() {
+
+        let mut mac = UpperMAC::new(CellConfig::default());
+        let mut receiver = DownlinkReceiver::new();
+
+        // BSCH in timeslot 2 of frame 18, then a normal burst in the next slot
+        for slot in 1..3 {
+            let time = TDMATime::at(slot, 17, 0, 0);
+            let burst = generate_dl_slot(&mut mac, &time).into_discontinuous();
+            let received = receiver.receive_dl_slot(&burst).unwrap();
+            assert!(!received.pdus.is_empty());
+            assert_eq!(received.access_assign, Some(mac.generate_access_assign(&time)));
+        }
+    }
+}
@@ -13,10 +13,73 @@ mod bits;
 mod lower_mac;
 mod bnch_helper;
 mod aach_helper;
+mod cell_config;
+mod config_file;
+mod fragmentation;
+mod association;
+mod random_access;
+mod reservation;
+mod iq_file;
 
 use bitvec::prelude::*;
-use crate::burst::DownlinkBurst;
-use crate::tdma_time::TDMATime;
+use std::io::Read;
+use crate::bits::Bits;
+use crate::burst::{Build, Synchroniser, SLOT_BITS};
+use crate::config_file::GeneratorConfig;
+use crate::dqpsk::BasebandGenerator;
+use crate::iq_file::{IQFormat, IQWriter};
+use crate::lower_mac::DownlinkReceiver;
+use crate::upper_mac::UpperMAC;
+
+/// Synchronise to a stream of bits (such as our own output) from stdin and print what's decoded
+fn receive() {
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+
+    // Ignore anything which isn't a bit, such as the line breaks between slots
+    let bits: Bits = input.chars()
+        .filter_map(|c| match c {
+            '0' => Some(false),
+            '1' => Some(true),
+            _ => None
+        })
+        .collect();
+
+    let mut synchroniser = Synchroniser::new(2);
+    let mut receiver = DownlinkReceiver::new();
+
+    let mut next_offset = None;
+    for burst in synchroniser.feed(bits) {
+
+        // Keep the receiver's time in step over any slots which were missed
+        if let Some(expected) = next_offset {
+            receiver.skip_slots((burst.offset - expected) / SLOT_BITS);
+        }
+        next_offset = Some(burst.offset + SLOT_BITS);
+
+        match receiver.receive_dl_slot(&burst.burst) {
+            Some(slot) => println!("{} {:?}", burst.offset, slot),
+            None => eprintln!("{} unable to decode burst", burst.offset)
+        }
+    }
+}
+
+/// Exit, describing the arguments
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {program} <slot count> [cell config] [--output <file.cf32|.cs16|.sigmf>] [--samples-per-symbol <n>] | rx"
+    );
+    std::process::exit(1);
+}
+
+/// Exit with a message if writing the I/Q output failed
+fn check_write(path: &str, result: std::io::Result<()>) {
+    if let Err(err) = result {
+        eprintln!("{path}: {err}");
+        std::process::exit(1);
+    }
+}
 
 fn main() {
 
@@ -25,30 +88,118 @@ fn main() {
     // Arguments
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <slot count>", args[0]);
-        std::process::exit(1);
+        usage(&args[0]);
     }
 
-    // Send 1000 0-bits first to prime the synchroniser
-    for i in 0..1000 {
-        print!("0");
+    if args[1] == "rx" {
+        receive();
+        return;
     }
 
-    let slot_count: i32 = args[1].parse().unwrap();
+    let slot_count: i32 = args[1].parse().unwrap_or_else(|_| {
+        eprintln!("invalid slot count {}", args[1]);
+        usage(&args[0])
+    });
 
-    let mut time = TDMATime::at(0, 17, 0, 0);
+    // Options, with anything else taken as the cell configuration
+    let mut config_path = None;
+    let mut output_path = None;
+    let mut samples_per_symbol = 4;
+    let mut options = args[2..].iter();
+    while let Some(arg) = options.next() {
+        match arg.as_str() {
+            "--output" => match options.next() {
+                Some(path) => output_path = Some(path),
+                None => {
+                    eprintln!("--output requires a file");
+                    usage(&args[0]);
+                }
+            },
+            "--samples-per-symbol" => {
+                samples_per_symbol = options.next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|&value| value > 0)
+                    .unwrap_or_else(|| {
+                        eprintln!("--samples-per-symbol must be a positive integer");
+                        std::process::exit(1);
+                    });
+            },
+            option if option.starts_with("--") => {
+                eprintln!("unknown option {option}");
+                usage(&args[0]);
+            },
+            _ if config_path.is_some() => {
+                eprintln!("unexpected argument {arg}");
+                usage(&args[0]);
+            },
+            _ => config_path = Some(arg)
+        }
+    }
+
+    // Cell configuration, if provided
+    let config = match config_path {
+        Some(path) => GeneratorConfig::load(std::path::Path::new(path)).unwrap_or_else(|err| {
+            eprintln!("{path}: {err}");
+            std::process::exit(1);
+        }),
+        None => GeneratorConfig::default()
+    };
+
+    // Modulated baseband is written to a file, in place of the bits on stdout
+    let mut baseband = None;
+    if let Some(path) = output_path {
+        let Some(format) = IQFormat::from_path(std::path::Path::new(path)) else {
+            eprintln!("{path}: unknown output format, expected .cf32, .cs16 or .sigmf");
+            std::process::exit(1);
+        };
+        let generator = BasebandGenerator::new(samples_per_symbol);
+        let writer = IQWriter::create(
+            std::path::Path::new(path),
+            format,
+            generator.sample_rate(),
+            config.cell.downlink_frequency(),
+            generator.delay()
+        ).unwrap_or_else(|err| {
+            eprintln!("{path}: {err}");
+            std::process::exit(1);
+        });
+        baseband = Some((generator, writer));
+    }
+
+    // Send 1000 0-bits first to prime the synchroniser
+    if baseband.is_none() {
+        for i in 0..1000 {
+            print!("0");
+        }
+    }
+
+    let mut mac = UpperMAC::new(config.cell);
+    let mut time = config.start_time;
 
     // Generate the first n slots
     for f in 0..slot_count {
 
         eprintln!("Slot {f} TDMA Time {time:?}");
 
-        let next_burst = lower_mac::generate_dl_slot(&time);
+        let next_burst = lower_mac::generate_dl_slot(&mut mac, &time);
         let built = next_burst.build();
-        println!("{}", bits_to_bin!(built));
+        match &mut baseband {
+            Some((generator, writer)) => {
+                let samples = generator.burst(&built);
+                check_write(output_path.unwrap(), writer.write_burst(&time, &samples));
+            },
+            None => println!("{}", bits_to_bin!(built))
+        }
 
         time = time.next();
     }
 
+    if let Some((mut generator, mut writer)) = baseband {
+        let path = output_path.unwrap();
+        let samples = generator.flush();
+        check_write(path, writer.write(&samples));
+        check_write(path, writer.finish());
+    }
+
 }
 
@@ -1,7 +1,8 @@
 use num_derive::{FromPrimitive, ToPrimitive};
-use crate::codec::{Writer, Encodable, SizedField};
+use crate::codec::{Writer, Encodable, SizedField, Decodable, DecodeError, Reader};
+use crate::tdma_time::TDMATime;
 
-#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum AccessCode {
     AccessCodeA = 0b00,
     AccessCodeB = 0b01,
@@ -15,7 +16,7 @@ impl SizedField for AccessCode {
     }
 }
 
-#[derive(FromPrimitive, ToPrimitive)]
+#[derive(Debug, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum BaseFrameLength {
     ReservedSubslot = 0b0000,
     CLCHSubslot = 0b0001,
@@ -41,6 +42,32 @@ impl SizedField for BaseFrameLength {
     }
 }
 
+impl BaseFrameLength {
+
+    /// The number of subslots in the access frame which starts with this subslot, if any
+    pub fn subslots(&self) -> Option<u32> {
+        match self {
+            BaseFrameLength::ReservedSubslot |
+            BaseFrameLength::CLCHSubslot |
+            BaseFrameLength::OngoingFrame => None,
+            BaseFrameLength::Subslots1 => Some(1),
+            BaseFrameLength::Subslots2 => Some(2),
+            BaseFrameLength::Subslots3 => Some(3),
+            BaseFrameLength::Subslots4 => Some(4),
+            BaseFrameLength::Subslots5 => Some(5),
+            BaseFrameLength::Subslots6 => Some(6),
+            BaseFrameLength::Subslots8 => Some(8),
+            BaseFrameLength::Subslots10 => Some(10),
+            BaseFrameLength::Subslots12 => Some(12),
+            BaseFrameLength::Subslots16 => Some(16),
+            BaseFrameLength::Subslots20 => Some(20),
+            BaseFrameLength::Subslots24 => Some(24),
+            BaseFrameLength::Subslots32 => Some(32)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct AccessField {
     pub access_code: AccessCode,
     pub base_frame_length: BaseFrameLength
@@ -53,6 +80,16 @@ impl Encodable for AccessField {
     }
 }
 
+impl Decodable for AccessField {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(AccessField {
+            access_code: AccessCode::decode(reader)?,
+            base_frame_length: BaseFrameLength::decode(reader)?
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum DownlinkUsageMarker {
     Reserved,
     CommonControl,
@@ -78,7 +115,22 @@ impl Encodable for DownlinkUsageMarker {
     }
 }
 
+impl Decodable for DownlinkUsageMarker {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("downlink_usage_marker", 6)? {
+            0b000000 => DownlinkUsageMarker::Unallocated,
+            0b000001 => DownlinkUsageMarker::AssignedControl,
+            0b000010 => DownlinkUsageMarker::CommonControl,
+            0b000011 => DownlinkUsageMarker::Reserved,
+            traffic_um => DownlinkUsageMarker::Traffic(traffic_um)
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum UplinkUsageMarker {
+    /// One of the values 0b000001 to 0b000011, which are reserved on the uplink
+    Reserved(u32),
     Unallocated,
     Traffic(u32)
 }
@@ -86,6 +138,7 @@ pub enum UplinkUsageMarker {
 impl Encodable for UplinkUsageMarker {
     fn encode(&self, writer: &mut Writer) {
         writer.write_int(match self {
+            UplinkUsageMarker::Reserved(_) => panic!("the uplink usage markers 0b000001 to 0b000011 are reserved"),
             UplinkUsageMarker::Unallocated => 0b000000,
             UplinkUsageMarker::Traffic(traffic_um) => {
                 if *traffic_um <= 0b000011 {
@@ -97,7 +150,18 @@ impl Encodable for UplinkUsageMarker {
     }
 }
 
+impl Decodable for UplinkUsageMarker {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("uplink_usage_marker", 6)? {
+            0b000000 => UplinkUsageMarker::Unallocated,
+            reserved @ 0b000001..=0b000011 => UplinkUsageMarker::Reserved(reserved),
+            traffic_um => UplinkUsageMarker::Traffic(traffic_um)
+        })
+    }
+}
+
 /// ACCESS-ASSIGN, as sent in a normal frame (Frames 1-17)
+#[derive(Debug, PartialEq)]
 pub enum AccessAssignNormalFrame {
     DownlinkCommonUplinkCommon {
         access_field_1: AccessField,
@@ -144,8 +208,32 @@ impl Encodable for AccessAssignNormalFrame {
     }
 }
 
+impl Decodable for AccessAssignNormalFrame {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("header", 2)? {
+            0b00 => AccessAssignNormalFrame::DownlinkCommonUplinkCommon {
+                access_field_1: AccessField::decode(reader)?,
+                access_field_2: AccessField::decode(reader)?
+            },
+            0b01 => AccessAssignNormalFrame::DownlinkDefinedUplinkCommonAndAssigned {
+                downlink_usage_marker: DownlinkUsageMarker::decode(reader)?,
+                access_field: AccessField::decode(reader)?
+            },
+            0b10 => AccessAssignNormalFrame::DownlinkDefinedUplinkAssignedOnly {
+                downlink_usage_marker: DownlinkUsageMarker::decode(reader)?,
+                access_field: AccessField::decode(reader)?
+            },
+            _ => AccessAssignNormalFrame::DownlinkDefinedUplinkDefined {
+                downlink_usage_marker: DownlinkUsageMarker::decode(reader)?,
+                uplink_usage_marker: UplinkUsageMarker::decode(reader)?
+            }
+        })
+    }
+}
+
 /// ACCESS-ASSIGN, as sent in the Control Frame (Frame 18)
 /// During the Control Frame, downlink is always for common control, so these options are omitted
+#[derive(Debug, PartialEq)]
 pub enum AccessAssignControlFrame {
     UplinkCommonOnly {
         access_field_1: AccessField,
@@ -192,11 +280,68 @@ impl Encodable for AccessAssignControlFrame {
     }
 }
 
+impl Decodable for AccessAssignControlFrame {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("header", 2)? {
+            0b00 => AccessAssignControlFrame::UplinkCommonOnly {
+                access_field_1: AccessField::decode(reader)?,
+                access_field_2: AccessField::decode(reader)?
+            },
+            0b01 => AccessAssignControlFrame::UplinkCommonAndAssigned {
+                access_field_1: AccessField::decode(reader)?,
+                access_field_2: AccessField::decode(reader)?
+            },
+            0b10 => AccessAssignControlFrame::UplinkAssignedOnly {
+                access_field_1: AccessField::decode(reader)?,
+                access_field_2: AccessField::decode(reader)?
+            },
+            _ => AccessAssignControlFrame::UplinkCommonAndAssignedTraffic {
+                access_field: AccessField::decode(reader)?,
+                uplink_usage_marker: UplinkUsageMarker::decode(reader)?
+            }
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum AccessAssign {
     NormalFrame(AccessAssignNormalFrame),
     ControlFrame(AccessAssignControlFrame)
 }
 
+impl AccessAssign {
+
+    /// Decode the ACCESS-ASSIGN PDU received at the given time.
+    /// The PDU has a different structure in the Control Frame (frame 18), so the time is needed to
+    /// select which to decode.
+    pub fn decode(reader: &mut Reader, time: &TDMATime) -> Result<Self, DecodeError> {
+        if time.is_control_frame() {
+            Ok(AccessAssign::ControlFrame(AccessAssignControlFrame::decode(reader)?))
+        } else {
+            Ok(AccessAssign::NormalFrame(AccessAssignNormalFrame::decode(reader)?))
+        }
+    }
+
+    /// The access fields describing the first and second uplink subslots of the slot. Where only
+    /// one access field is sent, it describes both subslots. If the uplink usage is defined, there
+    /// is no access field.
+    pub fn access_fields(&self) -> [Option<&AccessField>; 2] {
+        match self {
+            AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkCommonUplinkCommon { access_field_1, access_field_2 }) |
+            AccessAssign::ControlFrame(AccessAssignControlFrame::UplinkCommonOnly { access_field_1, access_field_2 }) |
+            AccessAssign::ControlFrame(AccessAssignControlFrame::UplinkCommonAndAssigned { access_field_1, access_field_2 }) |
+            AccessAssign::ControlFrame(AccessAssignControlFrame::UplinkAssignedOnly { access_field_1, access_field_2 }) =>
+                [Some(access_field_1), Some(access_field_2)],
+            AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkDefinedUplinkCommonAndAssigned { access_field, .. }) |
+            AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkDefinedUplinkAssignedOnly { access_field, .. }) |
+            AccessAssign::ControlFrame(AccessAssignControlFrame::UplinkCommonAndAssignedTraffic { access_field, .. }) =>
+                [Some(access_field), Some(access_field)],
+            AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkDefinedUplinkDefined { .. }) =>
+                [None, None]
+        }
+    }
+}
+
 impl Encodable for AccessAssign {
     fn encode(&self, writer: &mut Writer) {
         match self {
@@ -230,4 +375,51 @@ mod tests {
         assert_eq!(bits, from_bitstr("01000010000110"));
 
     }
+
+    #[test]
+    fn decodes_according_to_frame() {
+
+        let bits = from_bitstr("01000010000110");
+
+        // In frames 1-17 the header selects a downlink usage marker and access field
+        let decoded = AccessAssign::decode(&mut Reader::new(&bits), &TDMATime::at(0, 3, 0, 0)).unwrap();
+        assert_eq!(decoded, AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkDefinedUplinkCommonAndAssigned {
+            downlink_usage_marker: DownlinkUsageMarker::CommonControl,
+            access_field: AccessField {
+                access_code: AccessCode::AccessCodeA,
+                base_frame_length: BaseFrameLength::Subslots4,
+            },
+        }));
+
+        // Whereas in frame 18 the same bits are a pair of access fields
+        let decoded = AccessAssign::decode(&mut Reader::new(&bits), &TDMATime::at(0, 17, 0, 0)).unwrap();
+        assert_eq!(decoded, AccessAssign::ControlFrame(AccessAssignControlFrame::UplinkCommonAndAssigned {
+            access_field_1: AccessField {
+                access_code: AccessCode::AccessCodeA,
+                base_frame_length: BaseFrameLength::OngoingFrame,
+            },
+            access_field_2: AccessField {
+                access_code: AccessCode::AccessCodeA,
+                base_frame_length: BaseFrameLength::Subslots4,
+            },
+        }));
+    }
+
+    #[test]
+    fn decodes_reserved_usage_markers() {
+
+        let bits = from_bitstr("11 000011 000010");
+        let decoded = AccessAssign::decode(&mut Reader::new(&bits), &TDMATime::at(0, 0, 0, 0)).unwrap();
+        assert_eq!(decoded, AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkDefinedUplinkDefined {
+            downlink_usage_marker: DownlinkUsageMarker::Reserved,
+            uplink_usage_marker: UplinkUsageMarker::Reserved(0b000010),
+        }));
+
+        let bits = from_bitstr("11 000100 101010");
+        let decoded = AccessAssign::decode(&mut Reader::new(&bits), &TDMATime::at(0, 0, 0, 0)).unwrap();
+        assert_eq!(decoded, AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkDefinedUplinkDefined {
+            downlink_usage_marker: DownlinkUsageMarker::Traffic(0b000100),
+            uplink_usage_marker: UplinkUsageMarker::Traffic(0b101010),
+        }));
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::codec::{Decodable, Encodable, Reader, Writer};
+use crate::codec::{Decodable, Encodable, Reader, Writer, DecodeError};
 use crate::pdu::downlink::partial::{NeighbourCellBroadcast, CellServiceLevel, LateEntryInfo};
 
 #[derive(Debug)]
@@ -12,14 +12,14 @@ pub struct MLESyncPDU {
 
 
 impl Decodable for MLESyncPDU {
-    fn decode(reader: &mut Reader) -> Self {
-        MLESyncPDU {
-            mcc: reader.read_int(10),
-            mnc: reader.read_int(14),
-            neighbour_cell_broadcast: NeighbourCellBroadcast::decode(reader),
-            cell_service_level: CellServiceLevel::decode(reader),
-            late_entry_info: LateEntryInfo::decode(reader)
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(MLESyncPDU {
+            mcc: reader.read_int("mcc", 10)?,
+            mnc: reader.read_int("mnc", 14)?,
+            neighbour_cell_broadcast: NeighbourCellBroadcast::decode(reader)?,
+            cell_service_level: CellServiceLevel::decode(reader)?,
+            late_entry_info: LateEntryInfo::decode(reader)?
+        })
     }
 }
 
@@ -1,21 +1,21 @@
-use crate::codec::{Decodable, Encodable, Reader, Writer};
+use crate::codec::{Decodable, Encodable, Reader, Writer, DecodeError};
 use crate::pdu::downlink::BSServiceDetails;
 
 #[derive(Debug)]
 pub struct MLESysinfoPDU {
-    location_area: u32,
-    subscriber_class: u32, // todo: subscriber class is a bitmap
-    bs_service_details: BSServiceDetails
+    pub location_area: u32,
+    pub subscriber_class: u32, // todo: subscriber class is a bitmap
+    pub bs_service_details: BSServiceDetails
 }
 
 
 impl Decodable for MLESysinfoPDU {
-    fn decode(reader: &mut Reader) -> Self {
-        MLESysinfoPDU {
-            location_area: reader.read_int(14),
-            subscriber_class: reader.read_int(16),
-            bs_service_details: BSServiceDetails::decode(reader)
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(MLESysinfoPDU {
+            location_area: reader.read_int("location_area", 14)?,
+            subscriber_class: reader.read_int("subscriber_class", 16)?,
+            bs_service_details: BSServiceDetails::decode(reader)?
+        })
     }
 }
 
@@ -0,0 +1,100 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Optional, Writer, DecodeError};
+use crate::pdu::{read_tm_sdu, DownlinkMACPDUType};
+use crate::pdu::downlink::partial::SlotGranting;
+
+/// MAC-D-BLCK, a supplementary PDU carrying a TM-SDU addressed by event label, which is only sent
+/// on the SCH/F (EN 300 392-2 § 21.4.3.4)
+#[derive(Debug)]
+pub struct MACDBlckPDU {
+    pub fill_bit_indication: bool,
+    pub encryption_mode: u32,
+    pub event_label: u32,
+    pub immediate_napping_permission: bool,
+    pub slot_granting: Optional<SlotGranting>,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACDBlckPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type and subtype
+        let pdu_type = DownlinkMACPDUType::decode(reader)?;
+        if pdu_type != DownlinkMACPDUType::Supplementary {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
+        if reader.read_bool("pdu_subtype")? {
+            return Err(reader.invalid_value("pdu_subtype", 1));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let encryption_mode = reader.read_int("encryption_mode", 2)?;
+        let event_label = reader.read_int("event_label", 10)?;
+        let immediate_napping_permission = reader.read_bool("immediate_napping_permission")?;
+        let slot_granting = Optional::decode(reader)?;
+
+        Ok(MACDBlckPDU {
+            fill_bit_indication,
+            encryption_mode,
+            event_label,
+            immediate_napping_permission,
+            slot_granting,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, None, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACDBlckPDU {
+    fn encode(&self, writer: &mut Writer) {
+        DownlinkMACPDUType::Supplementary.encode(writer);
+        writer.write_bool(false);
+        writer.write_bool(self.fill_bit_indication);
+        writer.write_int(self.encryption_mode, 2);
+        writer.write_int(self.event_label, 10);
+        writer.write_bool(self.immediate_napping_permission);
+        self.slot_granting.encode(writer);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn encodes_and_decodes() {
+
+        let pdu = MACDBlckPDU {
+            fill_bit_indication: false,
+            encryption_mode: 0,
+            event_label: 0b1000000011,
+            immediate_napping_permission: true,
+            slot_granting: Optional::Absent,
+            tm_sdu_bits: from_bitstr("0110")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("
+            11 // PDU type (supplementary)
+            0 // subtype (MAC-D-BLCK)
+            0 // no fill bits
+            00 // encryption mode
+            1000000011 // event label
+            1 // immediate napping permitted
+            0 // no slot granting
+            0110 // tm-sdu
+        "));
+
+        let decoded = MACDBlckPDU::decode(&mut Reader::new(&bits)).unwrap();
+        assert_eq!(decoded.event_label, pdu.event_label);
+        assert!(decoded.immediate_napping_permission);
+        assert_eq!(decoded.tm_sdu_bits, pdu.tm_sdu_bits);
+    }
+}
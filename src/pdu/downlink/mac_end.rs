@@ -1,10 +1,118 @@
-use crate::codec::Optional;
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Optional, Writer, DecodeError};
+use crate::pdu::{read_tm_sdu, DownlinkMACPDUType};
 use crate::pdu::downlink::partial::{ChannelAllocation, Length, SlotGranting};
 
+/// MAC-END, carrying the last fragment of a TM-SDU (EN 300 392-2 § 21.4.3.3)
+#[derive(Debug)]
 pub struct MACEndPDU {
-    fill_bits: bool,
-    grant_is_on_current_channel: bool,
-    length: Length,
-    slot_granting: Optional<SlotGranting>,
-    channel_allocation: Optional<ChannelAllocation>
+    pub fill_bit_indication: bool,
+    pub grant_is_on_current_channel: bool,
+    pub length: Length,
+    pub slot_granting: Optional<SlotGranting>,
+    pub channel_allocation: Optional<ChannelAllocation>,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACEndPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type and subtype
+        let pdu_type = DownlinkMACPDUType::decode(reader)?;
+        if pdu_type != DownlinkMACPDUType::Fragment {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
+        if !reader.read_bool("pdu_subtype")? {
+            return Err(reader.invalid_value("pdu_subtype", 0));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let grant_is_on_current_channel = reader.read_bool("grant_is_on_current_channel")?;
+        let length = Length::decode(reader)?;
+        let slot_granting = Optional::decode(reader)?;
+        let channel_allocation = Optional::decode(reader)?;
+        let tm_sdu_bits = read_tm_sdu(reader, pdu_start, length.octets(), fill_bit_indication)?;
+
+        Ok(MACEndPDU {
+            fill_bit_indication,
+            grant_is_on_current_channel,
+            length,
+            slot_granting,
+            channel_allocation,
+            tm_sdu_bits
+        })
+    }
+}
+
+impl Encodable for MACEndPDU {
+    fn encode(&self, writer: &mut Writer) {
+        DownlinkMACPDUType::Fragment.encode(writer);
+        writer.write_bool(true);
+        writer.write_bool(self.fill_bit_indication);
+        writer.write_bool(self.grant_is_on_current_channel);
+        self.length.encode(writer);
+        self.slot_granting.encode(writer);
+        self.channel_allocation.encode(writer);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn decodes() {
+
+        let data = from_bitstr("
+            01 // PDU type (MAC-FRAG/MAC-END)
+            1 // subtype (MAC-END)
+            1 // fill bit indication
+            1 // grant is on the current channel
+            000100 // length (four octets)
+            1 // slot granting present
+            0011 // capacity allocation (three slots)
+            1110 // granting delay (frame eighteen)
+            0 // no channel allocation
+            1011 // tm-sdu
+            10 // fill bits
+            0000000000 // next PDU
+        ");
+
+        let pdu = MACEndPDU::decode(&mut Reader::new(&data)).unwrap();
+
+        assert!(pdu.fill_bit_indication);
+        assert!(pdu.grant_is_on_current_channel);
+        assert_eq!(pdu.length, Length::Octets(4));
+        assert!(matches!(pdu.slot_granting, Optional::Present(_)));
+        assert!(matches!(pdu.channel_allocation, Optional::Absent));
+        assert_eq!(pdu.tm_sdu_bits, from_bitstr("1011"));
+
+        // Re-encoding gives the same PDU, without the fill bits
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        assert_eq!(writer.done(), data[0..25]);
+    }
+
+    #[test]
+    fn encodes() {
+
+        let pdu = MACEndPDU {
+            fill_bit_indication: false,
+            grant_is_on_current_channel: false,
+            length: Length::Octets(3),
+            slot_granting: Optional::Absent,
+            channel_allocation: Optional::Absent,
+            tm_sdu_bits: from_bitstr("11001100 1010")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+
+        assert_eq!(writer.done(), from_bitstr("01 1 0 0 000011 0 0 11001100 1010"));
+    }
 }
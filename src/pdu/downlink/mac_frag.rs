@@ -1,4 +1,75 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use crate::pdu::{read_tm_sdu, DownlinkMACPDUType};
+
+/// MAC-FRAG, carrying a continuation fragment of a TM-SDU (EN 300 392-2 § 21.4.3.2)
 #[derive(Debug)]
 pub struct MACFragPDU {
-    fill_bits: bool
+    pub fill_bit_indication: bool,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACFragPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type and subtype
+        let pdu_type = DownlinkMACPDUType::decode(reader)?;
+        if pdu_type != DownlinkMACPDUType::Fragment {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
+        if reader.read_bool("pdu_subtype")? {
+            return Err(reader.invalid_value("pdu_subtype", 1));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+
+        Ok(MACFragPDU {
+            fill_bit_indication,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, None, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACFragPDU {
+    fn encode(&self, writer: &mut Writer) {
+        DownlinkMACPDUType::Fragment.encode(writer);
+        writer.write_bool(false);
+        writer.write_bool(self.fill_bit_indication);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+    use crate::codec::{add_fill_bits, FillBitCapacity};
+
+    #[test]
+    fn encodes_and_decodes() {
+
+        let pdu = MACFragPDU {
+            fill_bit_indication: true,
+            tm_sdu_bits: from_bitstr("1100101")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let mut bits = writer.done();
+        add_fill_bits(&mut bits, FillBitCapacity::Bits(16));
+
+        assert_eq!(bits, from_bitstr("
+            01 // PDU type (MAC-FRAG/MAC-END)
+            0 // subtype (MAC-FRAG)
+            1 // fill bit indication
+            1100101 // tm-sdu
+            10000 // fill bits
+        "));
+
+        let decoded = MACFragPDU::decode(&mut Reader::new(&bits)).unwrap();
+        assert_eq!(decoded.tm_sdu_bits, pdu.tm_sdu_bits);
+    }
 }
@@ -0,0 +1,112 @@
+use crate::bits::Bits;
+use crate::codec::{Decodable, DecodeError, Reader};
+use crate::pdu::{BroadcastPDUType, DownlinkMACPDUType};
+use crate::pdu::downlink::{Address, MACDBlckPDU, MACEndPDU, MACFragPDU, MACResourcePDU, Sysinfo, NULL_PDU_SIZE};
+
+/// A downlink MAC PDU, as carried at the start of a MAC block on the SCH, STCH or BNCH
+#[derive(Debug)]
+pub enum DownlinkMACPDU {
+    MACResource(MACResourcePDU),
+    MACFrag(MACFragPDU),
+    MACEnd(MACEndPDU),
+    MACDBlck(MACDBlckPDU),
+    Sysinfo(Sysinfo),
+    /// A PDU which cannot yet be decoded, holding the whole MAC block
+    Unsupported(Bits)
+}
+
+impl DownlinkMACPDU {
+
+    /// Parse the PDU at the start of a MAC block, based on its PDU type
+    pub fn parse(block: &Bits) -> Result<DownlinkMACPDU, DecodeError> {
+
+        let mut reader = Reader::new(block);
+        let pdu_type: Option<DownlinkMACPDUType> = num::FromPrimitive::from_u32(reader.read_int("pdu_type", 2)?);
+
+        Ok(match pdu_type {
+            Some(DownlinkMACPDUType::MACResource) =>
+                DownlinkMACPDU::MACResource(MACResourcePDU::decode(&mut Reader::new(block))?),
+            Some(DownlinkMACPDUType::Fragment) => match reader.read_bool("pdu_subtype")? {
+                false => DownlinkMACPDU::MACFrag(MACFragPDU::decode(&mut Reader::new(block))?),
+                true => DownlinkMACPDU::MACEnd(MACEndPDU::decode(&mut Reader::new(block))?)
+            },
+            Some(DownlinkMACPDUType::Broadcast) => {
+                let broadcast_type: Option<BroadcastPDUType> = num::FromPrimitive::from_u32(
+                    reader.read_int("broadcast_type", 2)?
+                );
+                match broadcast_type {
+                    Some(BroadcastPDUType::Sysinfo) =>
+                        DownlinkMACPDU::Sysinfo(Sysinfo::decode(&mut Reader::new(block))?),
+                    None => DownlinkMACPDU::Unsupported(block.clone())
+                }
+            },
+            Some(DownlinkMACPDUType::Supplementary) => match reader.read_bool("pdu_subtype")? {
+                false => DownlinkMACPDU::MACDBlck(MACDBlckPDU::decode(&mut Reader::new(block))?),
+                true => DownlinkMACPDU::Unsupported(block.clone())
+            },
+            None => DownlinkMACPDU::Unsupported(block.clone())
+        })
+    }
+
+    /// Parse every PDU associated in a MAC block (EN 300 392-2 § 23.4.2.3).
+    ///
+    /// A PDU with a length indication in octets may be followed by another, the rest end with the
+    /// block. The Null PDU ends the block early, as does too little space remaining for a PDU.
+    pub fn dissociate(block: &Bits) -> Result<Vec<DownlinkMACPDU>, DecodeError> {
+
+        let mut pdus = vec![];
+        let mut pdu_start = 0;
+
+        while block.len().saturating_sub(pdu_start) >= NULL_PDU_SIZE {
+
+            let pdu = DownlinkMACPDU::parse(&block[pdu_start..].to_bitvec())?;
+            let length = match &pdu {
+                DownlinkMACPDU::MACResource(pdu) if pdu.address == Address::NullPDU => None,
+                DownlinkMACPDU::MACResource(pdu) => pdu.length.octets(),
+                DownlinkMACPDU::MACEnd(pdu) => pdu.length.octets(),
+                _ => None
+            };
+
+            pdus.push(pdu);
+
+            match length {
+                Some(octets) => pdu_start += octets as usize * 8,
+                None => break
+            }
+        }
+
+        Ok(pdus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn parses_by_pdu_type() {
+
+        // Null MAC-RESOURCE
+        let block = from_bitstr("0000000000000000000000");
+        assert!(matches!(DownlinkMACPDU::parse(&block), Ok(DownlinkMACPDU::MACResource(_))));
+
+        let block = from_bitstr("0100000000000000000000");
+        assert!(matches!(DownlinkMACPDU::parse(&block), Ok(DownlinkMACPDU::MACFrag(_))));
+
+        let block = from_bitstr("0110000001000000000000");
+        assert!(matches!(DownlinkMACPDU::parse(&block), Ok(DownlinkMACPDU::MACEnd(_))));
+
+        let block = from_bitstr("1100000000000000000000");
+        assert!(matches!(DownlinkMACPDU::parse(&block), Ok(DownlinkMACPDU::MACDBlck(_))));
+
+        // Supplementary PDUs other than MAC-D-BLCK aren't supported yet
+        let block = from_bitstr("1110000000000000000000");
+        assert!(matches!(DownlinkMACPDU::parse(&block), Ok(DownlinkMACPDU::Unsupported(_))));
+
+        // Truncated MAC-RESOURCE
+        let block = from_bitstr("0000000");
+        assert!(matches!(DownlinkMACPDU::parse(&block), Err(DecodeError { field: "length", .. })));
+    }
+}
@@ -1,7 +1,10 @@
-use crate::codec::{Reader, Decodable, Encodable, Optional, Writer};
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Optional, Writer, DecodeError};
 use crate::pdu::downlink::partial::{Address, ChannelAllocation, Length, PowerControl, SlotGranting};
-use crate::pdu::DownlinkMACPDUType;
+use crate::pdu::{read_tm_sdu, DownlinkMACPDUType};
 
+/// Size of the Null PDU, which ends after the address type
+pub const NULL_PDU_SIZE: usize = 16;
 
 #[derive(Debug)]
 pub struct MACResourcePDU {
@@ -13,7 +16,8 @@ pub struct MACResourcePDU {
     pub address: Address,
     pub power_control: Optional<PowerControl>,
     pub slot_granting: Optional<SlotGranting>,
-    pub channel_allocation: Optional<ChannelAllocation>
+    pub channel_allocation: Optional<ChannelAllocation>,
+    pub tm_sdu_bits: Bits
 }
 
 impl MACResourcePDU {
@@ -23,33 +27,63 @@ impl MACResourcePDU {
             grant_is_on_current_channel: false,
             encryption_mode: 0,
             random_access_acknowledged: false,
-            length: Length::Reserved,
+            length: Length::NullPDU,
             address: Address::NullPDU,
             power_control: Optional::Absent,
             slot_granting: Optional::Absent,
             channel_allocation: Optional::Absent,
+            tm_sdu_bits: Bits::new()
         }
     }
 }
 
 impl Decodable for MACResourcePDU {
-    fn decode(reader: &mut Reader) -> Self {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
 
         // Decode & validate the PDU type
-        let pdu_type = DownlinkMACPDUType::decode(reader);
-        assert_eq!(pdu_type, DownlinkMACPDUType::MACResource);
+        let pdu_type = DownlinkMACPDUType::decode(reader)?;
+        if pdu_type != DownlinkMACPDUType::MACResource {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
 
-        MACResourcePDU {
-            fill_bit_indication: reader.read_bool(),
-            grant_is_on_current_channel: reader.read_bool(),
-            encryption_mode: reader.read_int(2),
-            random_access_acknowledged: reader.read_bool(),
-            length: Length::decode(reader),
-            address: Address::decode(reader),
-            power_control: Optional::decode(reader),
-            slot_granting: Optional::decode(reader),
-            channel_allocation: Optional::decode(reader)
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let grant_is_on_current_channel = reader.read_bool("grant_is_on_current_channel")?;
+        let encryption_mode = reader.read_int("encryption_mode", 2)?;
+        let random_access_acknowledged = reader.read_bool("random_access_acknowledged")?;
+        let length = Length::decode(reader)?;
+        let address = Address::decode(reader)?;
+
+        // The Null PDU ends with the address type, the rest of the block is fill bits
+        if address == Address::NullPDU {
+            return Ok(MACResourcePDU {
+                fill_bit_indication,
+                grant_is_on_current_channel,
+                encryption_mode,
+                random_access_acknowledged,
+                length,
+                ..MACResourcePDU::null()
+            });
         }
+
+        let power_control = Optional::decode(reader)?;
+        let slot_granting = Optional::decode(reader)?;
+        let channel_allocation = Optional::decode(reader)?;
+        let tm_sdu_bits = read_tm_sdu(reader, pdu_start, length.octets(), fill_bit_indication)?;
+
+        Ok(MACResourcePDU {
+            fill_bit_indication,
+            grant_is_on_current_channel,
+            encryption_mode,
+            random_access_acknowledged,
+            length,
+            address,
+            power_control,
+            slot_granting,
+            channel_allocation,
+            tm_sdu_bits
+        })
     }
 }
 
@@ -62,9 +96,13 @@ impl Encodable for MACResourcePDU {
         writer.write_bool(self.random_access_acknowledged);
         self.length.encode(writer);
         self.address.encode(writer);
+        if self.address == Address::NullPDU {
+            return;
+        }
         self.power_control.encode(writer);
         self.slot_granting.encode(writer);
         self.channel_allocation.encode(writer);
+        writer.write(&self.tm_sdu_bits);
     }
 }
 
@@ -100,7 +138,7 @@ mod tests {
         // Create a reader over the data
         let mut cur = Reader::new(&data);
 
-        let pdu = MACResourcePDU::decode(&mut cur);
+        let pdu = MACResourcePDU::decode(&mut cur).unwrap();
 
         // Fill bits are present
         assert_eq!(pdu.fill_bit_indication, true);
@@ -114,6 +152,13 @@ mod tests {
         // Address
         assert_eq!(pdu.address, Address::SSI { address: 1026 });
 
+        // The TM-SDU ends with the PDU, less the fill bits
+        assert_eq!(pdu.tm_sdu_bits, from_bitstr("
+            00011010010000100000
+            00000000000000000010
+            0111010101011000
+        "));
+
     }
 
     #[test]
@@ -131,6 +176,7 @@ mod tests {
             power_control: Optional::Absent,
             slot_granting: Optional::Absent,
             channel_allocation: Optional::Absent,
+            tm_sdu_bits: Bits::new()
         };
 
         let mut writer = Writer::new();
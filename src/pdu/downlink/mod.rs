@@ -5,7 +5,9 @@ mod sysinfo;
 mod sync;
 mod mac_end;
 mod mac_frag;
+mod mac_d_blck;
 mod access_define;
+mod mac_pdu;
 
 // Special MAC-to-MAC MLE PDUs
 mod d_mle_sync;
@@ -18,3 +20,7 @@ pub use d_mle_sysinfo::*;
 pub use partial::*;
 pub use mac_resource::*;
 pub use access_assign::*;
+pub use mac_frag::*;
+pub use mac_end::*;
+pub use mac_d_blck::*;
+pub use mac_pdu::DownlinkMACPDU;
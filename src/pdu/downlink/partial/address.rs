@@ -1,6 +1,6 @@
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub enum Address {
     NullPDU,
     SSI { address: u32 },
@@ -28,28 +28,28 @@ impl Address {
 }
 
 impl Decodable for Address {
-    fn decode(reader: &mut Reader) -> Self {
-        let address_type_field = reader.read_int(3);
-        match address_type_field {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let address_type_field = reader.read_int("address_type", 3)?;
+        Ok(match address_type_field {
             0b000 => Address::NullPDU,
-            0b001 => Address::SSI { address: reader.read_int(24) },
-            0b010 => Address::EventLabel { event_label: reader.read_int(10) },
-            0b011 => Address::USSI { ussi: reader.read_int(24) },
-            0b100 => Address::SMI { smi: reader.read_int(24) },
+            0b001 => Address::SSI { address: reader.read_int("ssi", 24)? },
+            0b010 => Address::EventLabel { event_label: reader.read_int("event_label", 10)? },
+            0b011 => Address::USSI { ussi: reader.read_int("ussi", 24)? },
+            0b100 => Address::SMI { smi: reader.read_int("smi", 24)? },
             0b101 => Address::SSIPlusEventLabel {
-                ssi: reader.read_int(24),
-                event_label: reader.read_int(10)
+                ssi: reader.read_int("ssi", 24)?,
+                event_label: reader.read_int("event_label", 10)?
             },
             0b110 => Address::SSIPlusUsageMarker {
-                ssi: reader.read_int(24),
-                usage_marker: reader.read_int(10)
+                ssi: reader.read_int("ssi", 24)?,
+                usage_marker: reader.read_int("usage_marker", 10)?
             },
             0b111 => Address::SMIPlusEventLabel {
-                smi: reader.read_int(24),
-                event_label: reader.read_int(10)
+                smi: reader.read_int("smi", 24)?,
+                event_label: reader.read_int("event_label", 10)?
             },
-            unknown => panic!("unknown address type {unknown}")
-        }
+            unknown => return Err(reader.invalid_value("address_type", unknown))
+        })
     }
 }
 
@@ -1,7 +1,7 @@
 use num_derive::{FromPrimitive, ToPrimitive};
 use crate::codec::SizedField;
 
-#[derive(Debug, FromPrimitive, ToPrimitive)]
+#[derive(Debug, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum AllocationType {
     Replacement = 0b00,
     Addition = 0b01,
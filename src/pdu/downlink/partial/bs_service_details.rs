@@ -1,46 +1,46 @@
-use crate::codec::{Decodable, Encodable, Reader, Writer};
+use crate::codec::{Decodable, Encodable, Reader, Writer, DecodeError};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BSServiceDetails {
-    registration_required: bool,
-    deregistration_required: bool,
-    priority_cell: bool,
-    cell_never_uses_minimum_mode: bool,
-    migration: bool,
-    system_wide_services: bool,
-    tetra_voice_service: bool,
-    circuit_mode_data_service: bool,
-    sndcp_service: bool,
-    air_interface_encryption_service: bool,
-    advanced_link_supported: bool
+    pub registration_required: bool,
+    pub deregistration_required: bool,
+    pub priority_cell: bool,
+    pub cell_never_uses_minimum_mode: bool,
+    pub migration: bool,
+    pub system_wide_services: bool,
+    pub tetra_voice_service: bool,
+    pub circuit_mode_data_service: bool,
+    pub sndcp_service: bool,
+    pub air_interface_encryption_service: bool,
+    pub advanced_link_supported: bool
 }
 
 impl Decodable for BSServiceDetails {
-    fn decode(reader: &mut Reader) -> Self {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
         let pdu = BSServiceDetails {
-            registration_required: reader.read_bool(),
-            deregistration_required: reader.read_bool(),
-            priority_cell: reader.read_bool(),
-            cell_never_uses_minimum_mode: reader.read_bool(),
-            migration: reader.read_bool(),
-            system_wide_services: reader.read_bool(),
-            tetra_voice_service: reader.read_bool(),
-            circuit_mode_data_service: reader.read_bool(),
+            registration_required: reader.read_bool("registration_required")?,
+            deregistration_required: reader.read_bool("deregistration_required")?,
+            priority_cell: reader.read_bool("priority_cell")?,
+            cell_never_uses_minimum_mode: reader.read_bool("cell_never_uses_minimum_mode")?,
+            migration: reader.read_bool("migration")?,
+            system_wide_services: reader.read_bool("system_wide_services")?,
+            tetra_voice_service: reader.read_bool("tetra_voice_service")?,
+            circuit_mode_data_service: reader.read_bool("circuit_mode_data_service")?,
             sndcp_service: false,
             air_interface_encryption_service: false,
             advanced_link_supported: false,
         };
 
         // Read the reserved bit
-        reader.read_bool();
+        reader.skip("reserved", 1)?;
 
         // Read the remaining bits in
-        BSServiceDetails {
-            sndcp_service: reader.read_bool(),
-            air_interface_encryption_service: reader.read_bool(),
-            advanced_link_supported: reader.read_bool(),
+        Ok(BSServiceDetails {
+            sndcp_service: reader.read_bool("sndcp_service")?,
+            air_interface_encryption_service: reader.read_bool("air_interface_encryption_service")?,
+            advanced_link_supported: reader.read_bool("advanced_link_supported")?,
             ..pdu
-        }
+        })
     }
 }
 
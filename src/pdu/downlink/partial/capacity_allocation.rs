@@ -1,19 +1,23 @@
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 
-#[derive(Debug)]
+/// The numbers of slots which may be allocated, coded 0001 to 1110 as for a reservation requirement
+pub const ALLOCATABLE_SLOTS: [u32; 14] = [1, 2, 3, 4, 5, 6, 8, 10, 13, 17, 24, 34, 51, 68];
+
+#[derive(Debug, PartialEq)]
 pub enum CapacityAllocation {
     FirstSubslot,
+    /// One of `ALLOCATABLE_SLOTS`. Any other number is rounded down when encoded.
     Slots(u32),
     SecondSubslot
 }
 
 impl Decodable for CapacityAllocation {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(4) {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("capacity_allocation", 4)? {
             0b0000 => Self::FirstSubslot,
             0b1111 => Self::SecondSubslot,
-            slots => Self::Slots(slots)
-        }
+            code => Self::Slots(ALLOCATABLE_SLOTS[code as usize - 1])
+        })
     }
 }
 
@@ -22,7 +26,31 @@ impl Encodable for CapacityAllocation {
         writer.write_int(match self {
             Self::FirstSubslot => 0b0000,
             Self::SecondSubslot => 0b1111,
-            Self::Slots(slots) => *slots
+            Self::Slots(slots) => ALLOCATABLE_SLOTS.iter()
+                .rposition(|allocatable| allocatable <= slots)
+                .map_or(1, |index| index as u32 + 1)
         }, 4);
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn codes_slots_as_reservation_requirement() {
+
+        let mut writer = Writer::new();
+        CapacityAllocation::Slots(13).encode(&mut writer);
+        CapacityAllocation::Slots(68).encode(&mut writer);
+        CapacityAllocation::SecondSubslot.encode(&mut writer);
+        let bits = writer.done();
+        assert_eq!(bits, from_bitstr("100111101111"));
+
+        let mut reader = Reader::new(&bits);
+        assert_eq!(CapacityAllocation::decode(&mut reader).unwrap(), CapacityAllocation::Slots(13));
+        assert_eq!(CapacityAllocation::decode(&mut reader).unwrap(), CapacityAllocation::Slots(68));
+    }
+}
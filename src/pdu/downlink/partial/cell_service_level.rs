@@ -1,4 +1,4 @@
-use crate::codec::{Decodable, Encodable, Reader, Writer};
+use crate::codec::{Decodable, Encodable, Reader, Writer, DecodeError};
 
 #[derive(Debug)]
 pub enum CellServiceLevel {
@@ -9,13 +9,13 @@ pub enum CellServiceLevel {
 }
 
 impl Decodable for CellServiceLevel {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(2) {
-            0b00 => Self::CellLoadUnknown,
-            0b01 => Self::LowCellLoad,
-            0b10 => Self::MediumCellLoad,
-            0b11 => Self::HighCellLoad,
-            _ => panic!("Invalid value for CellServiceLevel"),
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        match reader.read_int("cell_service_level", 2)? {
+            0b00 => Ok(Self::CellLoadUnknown),
+            0b01 => Ok(Self::LowCellLoad),
+            0b10 => Ok(Self::MediumCellLoad),
+            0b11 => Ok(Self::HighCellLoad),
+            invalid => Err(reader.invalid_value("cell_service_level", invalid)),
         }
     }
 }
@@ -1,34 +1,49 @@
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Optional, Writer, DecodeError};
 use super::{AllocationType, Direction, ExtendedCarrierNumbering, MonitoringPatterns, TimeslotAssigned};
 
-#[derive(Debug)]
+/// Channel allocation element (EN 300 392-2 § 21.5.2)
+#[derive(Debug, PartialEq)]
 pub struct ChannelAllocation {
-    allocation_type: AllocationType,
-    timeslot_assigned: TimeslotAssigned,
-    direction: Direction,
-    clch_permission: bool,
-    cell_change: bool,
-    carrier_number: u32,
-    extended_carrier_numbering: Option<ExtendedCarrierNumbering>,
-    reverse_operation: bool,
-    monitoring_pattern: MonitoringPatterns,
-    frame_18_monitoring_pattern: MonitoringPatterns
+    pub allocation_type: AllocationType,
+    pub timeslot_assigned: TimeslotAssigned,
+    pub direction: Direction,
+    pub clch_permission: bool,
+    pub cell_change: bool,
+    pub carrier_number: u32,
+    pub extended_carrier_numbering: Optional<ExtendedCarrierNumbering>,
+    pub monitoring_pattern: MonitoringPatterns,
+    /// Only present when there's no monitoring pattern for frames 1-17
+    pub frame_18_monitoring_pattern: Option<MonitoringPatterns>
 }
 
 impl Decodable for ChannelAllocation {
-    fn decode(reader: &mut Reader) -> Self {
-        ChannelAllocation {
-            allocation_type: num::FromPrimitive::from_u32(reader.read_int(2)).unwrap(),
-            timeslot_assigned: TimeslotAssigned::decode(reader),
-            direction: Direction::Downlink,
-            clch_permission: false,
-            cell_change: false,
-            carrier_number: 0,
-            extended_carrier_numbering: None,
-            reverse_operation: false,
-            monitoring_pattern: MonitoringPatterns::None,
-            frame_18_monitoring_pattern: MonitoringPatterns::None,
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let allocation_type = AllocationType::decode(reader)?;
+        let timeslot_assigned = TimeslotAssigned::decode(reader)?;
+        let direction = Direction::decode(reader)?;
+        let clch_permission = reader.read_bool("clch_permission")?;
+        let cell_change = reader.read_bool("cell_change")?;
+        let carrier_number = reader.read_int("carrier_number", 12)?;
+        let extended_carrier_numbering = Optional::decode(reader)?;
+        let monitoring_pattern = MonitoringPatterns::decode(reader)?;
+
+        let frame_18_monitoring_pattern = match monitoring_pattern {
+            MonitoringPatterns::None => Some(MonitoringPatterns::decode(reader)?),
+            _ => None
+        };
+
+        Ok(ChannelAllocation {
+            allocation_type,
+            timeslot_assigned,
+            direction,
+            clch_permission,
+            cell_change,
+            carrier_number,
+            extended_carrier_numbering,
+            monitoring_pattern,
+            frame_18_monitoring_pattern
+        })
     }
 }
 
@@ -37,5 +52,101 @@ impl Encodable for ChannelAllocation {
         self.allocation_type.encode(writer);
         self.timeslot_assigned.encode(writer);
         self.direction.encode(writer);
+        writer.write_bool(self.clch_permission);
+        writer.write_bool(self.cell_change);
+        writer.write_int(self.carrier_number, 12);
+        self.extended_carrier_numbering.encode(writer);
+        self.monitoring_pattern.encode(writer);
+
+        if self.monitoring_pattern == MonitoringPatterns::None {
+            self.frame_18_monitoring_pattern
+                .as_ref()
+                .expect("the frame 18 monitoring pattern is required when there's no monitoring pattern")
+                .encode(writer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+    use crate::pdu::downlink::partial::Offset;
+
+    #[test]
+    fn encodes_and_decodes() {
+
+        let channel_allocation = ChannelAllocation {
+            allocation_type: AllocationType::Replacement,
+            timeslot_assigned: TimeslotAssigned::Specific((false, true, false, false)),
+            direction: Direction::Both,
+            clch_permission: true,
+            cell_change: false,
+            carrier_number: 1521,
+            extended_carrier_numbering: Optional::Absent,
+            monitoring_pattern: MonitoringPatterns::None,
+            frame_18_monitoring_pattern: Some(MonitoringPatterns::Two)
+        };
+
+        let mut writer = Writer::new();
+        channel_allocation.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("
+            00 // allocation type (replace)
+            0100 // timeslot assigned (two)
+            11 // up/downlink assigned (both)
+            1 // CLCH permission
+            0 // no cell change
+            010111110001 // carrier number
+            0 // no extended carrier numbering
+            00 // no monitoring pattern
+            10 // frame eighteen monitoring pattern (two)
+        "));
+
+        assert_eq!(ChannelAllocation::decode(&mut Reader::new(&bits)), Ok(channel_allocation));
+    }
+
+    #[test]
+    fn encodes_and_decodes_extended_carrier_numbering() {
+
+        let channel_allocation = ChannelAllocation {
+            allocation_type: AllocationType::QuitAndGoTo,
+            timeslot_assigned: TimeslotAssigned::AppropriateCCH,
+            direction: Direction::Downlink,
+            clch_permission: false,
+            cell_change: true,
+            carrier_number: 0xABC,
+            extended_carrier_numbering: Optional::Present(ExtendedCarrierNumbering {
+                frequency_band: 4,
+                offset: Offset::Minus6_25kHz,
+                duplex_spacing: 5,
+                reverse_operation: true
+            }),
+            monitoring_pattern: MonitoringPatterns::Three,
+            frame_18_monitoring_pattern: None
+        };
+
+        let mut writer = Writer::new();
+        channel_allocation.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("
+            10 // allocation type (quit and go)
+            0000 // timeslot assigned (appropriate CCH)
+            01 // up/downlink assigned (downlink)
+            0 // no CLCH permission
+            1 // cell change
+            101010111100 // carrier number
+            1 // extended carrier numbering present
+            0100 // frequency band
+            10 // offset (minus six and a quarter kHz)
+            101 // duplex spacing
+            1 // reverse operation
+            11 // three monitoring patterns
+        "));
+
+        assert_eq!(ChannelAllocation::decode(&mut Reader::new(&bits)), Ok(channel_allocation));
     }
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 use num_derive::{FromPrimitive, ToPrimitive};
 use crate::codec::SizedField;
 
-#[derive(Debug, FromPrimitive, ToPrimitive)]
+/// Up/downlink assigned. The value 0b00 indicates an augmented channel allocation, which isn't
+/// supported.
+#[derive(Debug, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum Direction {
     Downlink = 0b01,
     Uplink = 0b10,
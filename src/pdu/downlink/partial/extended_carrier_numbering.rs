@@ -1,6 +1,30 @@
-#[derive(Debug)]
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use crate::pdu::downlink::partial::Offset;
+
+#[derive(Debug, PartialEq)]
 pub struct ExtendedCarrierNumbering {
-    frequency_band: u32,
-    offset: u32,
-    duplex_spacing: u32,
+    pub frequency_band: u32,
+    pub offset: Offset,
+    pub duplex_spacing: u32,
+    pub reverse_operation: bool
 }
+
+impl Decodable for ExtendedCarrierNumbering {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(ExtendedCarrierNumbering {
+            frequency_band: reader.read_int("frequency_band", 4)?,
+            offset: Offset::decode(reader)?,
+            duplex_spacing: reader.read_int("duplex_spacing", 3)?,
+            reverse_operation: reader.read_bool("reverse_operation")?
+        })
+    }
+}
+
+impl Encodable for ExtendedCarrierNumbering {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_int(self.frequency_band, 4);
+        self.offset.encode(writer);
+        writer.write_int(self.duplex_spacing, 3);
+        writer.write_bool(self.reverse_operation);
+    }
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum GrantingDelay {
     AtNextOpportunity,
     After(u32),
@@ -9,13 +9,13 @@ pub enum GrantingDelay {
 }
 
 impl Decodable for GrantingDelay {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(4) {
-            0b0000 => Self::AtNextOpportunity,
-            after @ 0b0001 ..= 0b1101 => Self::After(after),
-            0b1110 => Self::Frame18,
-            0b1111 => Self::WaitForAnotherMessage,
-            unknown => panic!("unknown slot granting delay {unknown}")
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        match reader.read_int("granting_delay", 4)? {
+            0b0000 => Ok(Self::AtNextOpportunity),
+            after @ 0b0001 ..= 0b1101 => Ok(Self::After(after)),
+            0b1110 => Ok(Self::Frame18),
+            0b1111 => Ok(Self::WaitForAnotherMessage),
+            unknown => Err(reader.invalid_value("granting_delay", unknown))
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::codec::{Decodable, Encodable, Reader, Writer};
+use crate::codec::{Decodable, Encodable, Reader, Writer, DecodeError};
 
 #[derive(Debug)]
 pub struct LateEntryInfo {
@@ -6,10 +6,10 @@ pub struct LateEntryInfo {
 }
 
 impl Decodable for LateEntryInfo {
-    fn decode(reader: &mut Reader) -> Self {
-        LateEntryInfo {
-            late_entry_supported: reader.read_bool()
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(LateEntryInfo {
+            late_entry_supported: reader.read_bool("late_entry_supported")?
+        })
     }
 }
 
@@ -1,5 +1,5 @@
 use bitvec::macros::internal::funty::Fundamental;
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 
 const LENGTH_SIZE: usize = 6;
 
@@ -12,10 +12,20 @@ pub enum Length {
     StartOfFragmentation
 }
 
+impl Length {
+    /// The length of the MAC PDU in octets, if indicated
+    pub fn octets(&self) -> Option<u32> {
+        match self {
+            Length::Octets(octets) => Some(octets.as_u32()),
+            _ => None
+        }
+    }
+}
+
 impl Decodable for Length {
-    fn decode(reader: &mut Reader) -> Self {
-        let length_field = reader.read_int(LENGTH_SIZE);
-        match length_field {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let length_field = reader.read_int("length", LENGTH_SIZE)?;
+        Ok(match length_field {
             0b000000 | 0b000001 => Self::Reserved,
             0b000010 => Self::NullPDU,
             0b000011 => Self::Reserved,
@@ -23,7 +33,7 @@ impl Decodable for Length {
             0b111110 => Self::SecondHalfSlotStolen,
             0b111111 => Self::StartOfFragmentation,
             octets => Self::Octets(octets.as_usize()),
-        }
+        })
     }
 }
 
@@ -1,8 +1,10 @@
 pub use self::address::Address;
 pub use self::allocation_type::AllocationType;
+pub use self::capacity_allocation::{CapacityAllocation, ALLOCATABLE_SLOTS};
 pub use self::channel_allocation::ChannelAllocation;
 pub use self::direction::Direction;
 pub use self::extended_carrier_numbering::ExtendedCarrierNumbering;
+pub use self::granting_delay::GrantingDelay;
 pub use self::length::Length;
 pub use self::monitoring_patterns::MonitoringPatterns;
 pub use self::power_control::PowerControl;
@@ -1,7 +1,16 @@
-#[derive(Debug)]
+use num_derive::{FromPrimitive, ToPrimitive};
+use crate::codec::SizedField;
+
+#[derive(Debug, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum MonitoringPatterns {
-    None,
-    One,
-    Two,
-    Three
+    None = 0b00,
+    One = 0b01,
+    Two = 0b10,
+    Three = 0b11
+}
+
+impl SizedField for MonitoringPatterns {
+    fn size() -> usize {
+        2
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::codec::{Decodable, Encodable, Reader, Writer};
+use crate::codec::{Decodable, Encodable, Reader, Writer, DecodeError};
 
 #[derive(Debug)]
 pub struct NeighbourCellBroadcast {
@@ -7,17 +7,17 @@ pub struct NeighbourCellBroadcast {
 }
 
 impl Decodable for NeighbourCellBroadcast {
-    fn decode(reader: &mut Reader) -> Self {
-        NeighbourCellBroadcast {
-            d_nwrk_broadcast_supported: reader.read_bool(),
-            d_nwrk_enquiry_supported: reader.read_bool(),
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(NeighbourCellBroadcast {
+            d_nwrk_broadcast_supported: reader.read_bool("d_nwrk_broadcast_supported")?,
+            d_nwrk_enquiry_supported: reader.read_bool("d_nwrk_enquiry_supported")?,
+        })
     }
 }
 
 impl Encodable for NeighbourCellBroadcast {
     fn encode(&self, writer: &mut Writer) {
-        writer.write_bool(self.d_nwrk_enquiry_supported);
+        writer.write_bool(self.d_nwrk_broadcast_supported);
         writer.write_bool(self.d_nwrk_enquiry_supported);
     }
 }
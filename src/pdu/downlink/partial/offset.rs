@@ -1,7 +1,7 @@
 use num_derive::{FromPrimitive, ToPrimitive};
 use crate::codec::SizedField;
 
-#[derive(FromPrimitive, ToPrimitive, Debug)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, PartialEq)]
 pub enum Offset {
     NoOffset = 0b00,
     Plus6_25kHz = 0b01,
@@ -9,6 +9,19 @@ pub enum Offset {
     Plus12_5kHz = 0b11
 }
 
+impl Offset {
+
+    /// The offset from the nominal carrier frequency, in Hz
+    pub fn hertz(&self) -> i64 {
+        match self {
+            Offset::NoOffset => 0,
+            Offset::Plus6_25kHz => 6_250,
+            Offset::Minus6_25kHz => -6_250,
+            Offset::Plus12_5kHz => 12_500
+        }
+    }
+}
+
 impl SizedField for Offset {
     fn size() -> usize {
         2
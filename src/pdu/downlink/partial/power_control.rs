@@ -1,4 +1,4 @@
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 
 #[derive(Debug)]
 pub enum PowerControl {
@@ -11,15 +11,15 @@ pub enum PowerControl {
 }
 
 impl Decodable for PowerControl {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(4) {
-            0b0000 => PowerControl::NoChange,
-            inc @ 0b0001 ..= 0b0110 => PowerControl::IncreaseBySteps(inc),
-            0b0111 => PowerControl::MaximumPathDelayExceeded,
-            0b1000 => PowerControl::OpenLoop,
-            dec @ 0b1001 ..= 0b1110 => PowerControl::DecreaseBySteps(dec - 8),
-            0b1111 => PowerControl::RadioUplinkFailure,
-            unknown @ _ => panic!("unknown power control information {unknown}")
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        match reader.read_int("power_control", 4)? {
+            0b0000 => Ok(PowerControl::NoChange),
+            inc @ 0b0001 ..= 0b0110 => Ok(PowerControl::IncreaseBySteps(inc)),
+            0b0111 => Ok(PowerControl::MaximumPathDelayExceeded),
+            0b1000 => Ok(PowerControl::OpenLoop),
+            dec @ 0b1001 ..= 0b1110 => Ok(PowerControl::DecreaseBySteps(dec - 8)),
+            0b1111 => Ok(PowerControl::RadioUplinkFailure),
+            unknown => Err(reader.invalid_value("power_control", unknown))
         }
     }
 }
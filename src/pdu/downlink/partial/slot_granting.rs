@@ -1,19 +1,19 @@
-use crate::codec::{Reader, Decodable, Encodable, Writer};
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 use super::granting_delay::GrantingDelay;
 use super::capacity_allocation::CapacityAllocation;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct SlotGranting {
-    capacity_allocation: CapacityAllocation,
-    granting_delay: GrantingDelay
+    pub capacity_allocation: CapacityAllocation,
+    pub granting_delay: GrantingDelay
 }
 
 impl Decodable for SlotGranting {
-    fn decode(reader: &mut Reader) -> Self {
-        SlotGranting {
-            capacity_allocation: CapacityAllocation::decode(reader),
-            granting_delay: GrantingDelay::decode(reader)
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(SlotGranting {
+            capacity_allocation: CapacityAllocation::decode(reader)?,
+            granting_delay: GrantingDelay::decode(reader)?
+        })
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::codec::{Writer, Decodable, Encodable, Reader};
+use crate::codec::{Writer, Decodable, Encodable, Reader, DecodeError};
 
 pub type Timeslots = (bool, bool, bool, bool);
 
@@ -14,13 +14,13 @@ impl Encodable for Timeslots {
 }
 
 impl Decodable for Timeslots {
-    fn decode(reader: &mut Reader) -> Self {
-        let bitmap = reader.read_int(4);
-        (
-            bitmap & 0b0001 > 0,
-            bitmap & 0b0010 > 0,
-            bitmap & 0b0100 > 0,
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let bitmap = reader.read_int("timeslots", 4)?;
+        Ok((
             bitmap & 0b1000 > 0,
-        )
+            bitmap & 0b0100 > 0,
+            bitmap & 0b0010 > 0,
+            bitmap & 0b0001 > 0,
+        ))
     }
 }
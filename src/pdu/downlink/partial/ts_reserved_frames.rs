@@ -1,4 +1,5 @@
 use num_derive::{FromPrimitive, ToPrimitive};
+use crate::codec::SizedField;
 
 #[derive(Debug, PartialEq, FromPrimitive, ToPrimitive)]
 pub enum TSReservedFrames {
@@ -11,3 +12,9 @@ pub enum TSReservedFrames {
     Reserve12 = 0b110,
     Reserve18 = 0b111
 }
+
+impl SizedField for TSReservedFrames {
+    fn size() -> usize {
+        3
+    }
+}
@@ -1,5 +1,5 @@
 use crate::bits::Bits;
-use crate::codec::{Writer, Decodable, Encodable, Reader};
+use crate::codec::{Writer, Decodable, Encodable, Reader, DecodeError};
 use crate::pdu::downlink::partial::{SharingMode, TSReservedFrames};
 
 #[derive(Debug)]
@@ -17,31 +17,31 @@ pub struct Sync {
 }
 
 impl Decodable for Sync {
-    fn decode(reader: &mut Reader) -> Self {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
 
         let result = Sync {
-            system_code: reader.read_int(4),
-            colour_code: reader.read_int(6),
-            timeslot_number: reader.read_int(2),
-            frame_number: reader.read_int(5),
-            multiframe_number: reader.read_int(6),
-            sharing_mode: num::FromPrimitive::from_u32(reader.read_int(2)).unwrap(),
-            ts_reserved_frames: num::FromPrimitive::from_u32(reader.read_int(3)).unwrap(),
-            u_plane_dtx: reader.read_bool(),
-            frame_18_extension: reader.read_bool(),
+            system_code: reader.read_int("system_code", 4)?,
+            colour_code: reader.read_int("colour_code", 6)?,
+            timeslot_number: reader.read_int("timeslot_number", 2)?,
+            frame_number: reader.read_int("frame_number", 5)?,
+            multiframe_number: reader.read_int("multiframe_number", 6)?,
+            sharing_mode: SharingMode::decode(reader)?,
+            ts_reserved_frames: TSReservedFrames::decode(reader)?,
+            u_plane_dtx: reader.read_bool("u_plane_dtx")?,
+            frame_18_extension: reader.read_bool("frame_18_extension")?,
             tm_sdu_bits: Bits::new()
         };
 
         // Consume the reserved bit(s)
-        reader.read_bool();
+        reader.skip("reserved", 1)?;
 
         // Read the TM-SDU bits
-        let bits = reader.read(29);
+        let bits = reader.read("tm_sdu_bits", 29)?;
 
-        Sync {
+        Ok(Sync {
             tm_sdu_bits: bits,
             ..result
-        }
+        })
     }
 }
 
@@ -113,7 +113,7 @@ mod tests {
         ");
 
         let mut reader = Reader::new(&data);
-        let sync_pdu = Sync::decode(&mut reader);
+        let sync_pdu = Sync::decode(&mut reader).unwrap();
 
         assert_eq!(sync_pdu.system_code, 0);
         assert_eq!(sync_pdu.colour_code, 63);
@@ -1,6 +1,6 @@
 use num_derive::{FromPrimitive, ToPrimitive};
 use crate::bits::Bits;
-use crate::codec::{Writer, Encodable, SizedField, Decodable, Reader};
+use crate::codec::{Writer, Encodable, SizedField, Decodable, Reader, DecodeError};
 use crate::pdu::downlink::partial::{Offset, Timeslots};
 use crate::pdu::{BroadcastPDUType, DownlinkMACPDUType};
 
@@ -30,15 +30,15 @@ impl Encodable for HyperframeOrCipherKey {
 }
 
 impl Decodable for HyperframeOrCipherKey {
-    fn decode(reader: &mut Reader) -> Self {
-        if reader.read_bool() {
-            HyperframeOrCipherKey::CipherKey {
-                cck_id_or_key_version_number: reader.read_int(16)
-            }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        if reader.read_bool("hyperframe_or_cipher_key_flag")? {
+            Ok(HyperframeOrCipherKey::CipherKey {
+                cck_id_or_key_version_number: reader.read_int("cck_id_or_key_version_number", 16)?
+            })
         } else {
-            HyperframeOrCipherKey::Hyperframe {
-                hyperframe_number: reader.read_int(16)
-            }
+            Ok(HyperframeOrCipherKey::Hyperframe {
+                hyperframe_number: reader.read_int("hyperframe_number", 16)?
+            })
         }
     }
 }
@@ -53,7 +53,7 @@ impl Encodable for TSModeBitmap {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum Immediate {
     AlwaysRandomise,
     AfterFrames(u32),
@@ -71,16 +71,16 @@ impl Encodable for Immediate {
 }
 
 impl Decodable for Immediate {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(4) {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("immediate", 4)? {
             0b0000 => Immediate::AlwaysRandomise,
             0b1111 => Immediate::Immediate,
             n => Immediate::AfterFrames(n),
-        }
+        })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum TimeslotPointer {
     SameAsDownlink,
     InTimeslots(Timeslots)
@@ -96,15 +96,20 @@ impl Encodable for TimeslotPointer {
 }
 
 impl Decodable for TimeslotPointer {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(4) {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("timeslot_pointer", 4)? {
             0b0000 => TimeslotPointer::SameAsDownlink,
-            _ => TimeslotPointer::InTimeslots(Timeslots::decode(reader)),
-        }
+            timeslots => TimeslotPointer::InTimeslots((
+                timeslots & 0b1000 != 0,
+                timeslots & 0b0100 != 0,
+                timeslots & 0b0010 != 0,
+                timeslots & 0b0001 != 0
+            )),
+        })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct AccessCodeDefinition {
     pub(crate) immediate: Immediate,
     pub(crate) waiting_time_opportunities: u32,
@@ -125,7 +130,7 @@ impl Encodable for AccessCodeDefinition {
     }
 }
 
-#[derive(FromPrimitive, ToPrimitive, Debug)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone)]
 pub enum SDSTLAddressingMethod {
     Reserved = 0b00,
     ServiceCentreAddressingPreferred = 0b01,
@@ -139,11 +144,11 @@ impl SizedField for SDSTLAddressingMethod {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct ExtendedServicesBroadcast {
     // todo: this is a whole separate structure described in EN 300 392-7
-    security_information: u32,
-    sds_tl_addressing_method: SDSTLAddressingMethod
+    pub(crate) security_information: u32,
+    pub(crate) sds_tl_addressing_method: SDSTLAddressingMethod
 }
 
 impl Encodable for ExtendedServicesBroadcast {
@@ -155,21 +160,22 @@ impl Encodable for ExtendedServicesBroadcast {
 }
 
 impl Decodable for ExtendedServicesBroadcast {
-    fn decode(reader: &mut Reader) -> Self {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
 
         let result = ExtendedServicesBroadcast {
-            security_information: reader.read_int(8),
-            sds_tl_addressing_method: SDSTLAddressingMethod::decode(reader),
+            security_information: reader.read_int("security_information", 8)?,
+            sds_tl_addressing_method: SDSTLAddressingMethod::decode(reader)?,
         };
 
         // reserved, set to all-0
-        assert_eq!(reader.read_int(10), 0);
-
-        result
+        match reader.read_int("reserved", 10)? {
+            0 => Ok(result),
+            reserved => Err(reader.invalid_value("reserved", reserved))
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum OptionalField {
     TSModeEvenMultiframe(TSModeBitmap),
     TSModeOddMultiframe(TSModeBitmap),
@@ -201,38 +207,33 @@ impl Encodable for OptionalField {
 }
 
 impl Decodable for OptionalField {
-    fn decode(reader: &mut Reader) -> Self {
-        match reader.read_int(2) {
-            0b00 => OptionalField::TSModeEvenMultiframe({
-                let mut bitmap = [false; 20];
-                for bit in bitmap.iter_mut() {
-                    *bit = reader.read_bool();
-                }
-                bitmap
-            }),
-            0b01 => OptionalField::TSModeOddMultiframe({
-                let mut bitmap = [false; 20];
-                for bit in bitmap.iter_mut() {
-                    *bit = reader.read_bool();
-                }
-                bitmap
-            }),
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("optional_field_flag", 2)? {
+            0b00 => OptionalField::TSModeEvenMultiframe(decode_ts_mode_bitmap(reader)?),
+            0b01 => OptionalField::TSModeOddMultiframe(decode_ts_mode_bitmap(reader)?),
             0b10 => OptionalField::DefaultAccessCodeA(AccessCodeDefinition {
-                immediate: Immediate::decode(reader),
-                waiting_time_opportunities: reader.read_int(4),
-                number_of_attempts: reader.read_int(4),
-                frame_length_x4: reader.read_bool(),
-                timeslot: TimeslotPointer::decode(reader),
-                minimum_priority: reader.read_int(3),
+                immediate: Immediate::decode(reader)?,
+                waiting_time_opportunities: reader.read_int("waiting_time_opportunities", 4)?,
+                number_of_attempts: reader.read_int("number_of_attempts", 4)?,
+                frame_length_x4: reader.read_bool("frame_length_x4")?,
+                timeslot: TimeslotPointer::decode(reader)?,
+                minimum_priority: reader.read_int("minimum_priority", 3)?,
             }),
-            0b11 => OptionalField::ExtendedServicesBroadcast(
-                ExtendedServicesBroadcast::decode(reader)
+            _ => OptionalField::ExtendedServicesBroadcast(
+                ExtendedServicesBroadcast::decode(reader)?
             ),
-            _ => panic!("invalid OptionalField discriminator"),
-        }
+        })
     }
 }
 
+fn decode_ts_mode_bitmap(reader: &mut Reader) -> Result<TSModeBitmap, DecodeError> {
+    let mut bitmap = [false; 20];
+    for bit in bitmap.iter_mut() {
+        *bit = reader.read_bool("ts_mode_bitmap")?;
+    }
+    Ok(bitmap)
+}
+
 
 #[derive(FromPrimitive, ToPrimitive, Debug)]
 pub(crate) enum NumberOfCommonSCCH {
@@ -248,7 +249,7 @@ impl SizedField for NumberOfCommonSCCH {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RFParameters {
     pub(crate) ms_txpwr_max_cell: u32,
     pub(crate) rxlev_access_min: u32,
@@ -266,13 +267,13 @@ impl Encodable for RFParameters {
 }
 
 impl Decodable for RFParameters {
-    fn decode(reader: &mut Reader) -> Self {
-        RFParameters {
-            ms_txpwr_max_cell: reader.read_int(3),
-            rxlev_access_min: reader.read_int(4),
-            access_parameter: reader.read_int(4),
-            radio_downlink_timeout: reader.read_int(4),
-        }
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(RFParameters {
+            ms_txpwr_max_cell: reader.read_int("ms_txpwr_max_cell", 3)?,
+            rxlev_access_min: reader.read_int("rxlev_access_min", 4)?,
+            access_parameter: reader.read_int("access_parameter", 4)?,
+            radio_downlink_timeout: reader.read_int("radio_downlink_timeout", 4)?,
+        })
     }
 }
 
@@ -292,6 +293,8 @@ pub struct Sysinfo {
 
 impl Encodable for Sysinfo {
     fn encode(&self, writer: &mut Writer) {
+        DownlinkMACPDUType::Broadcast.encode(writer);
+        BroadcastPDUType::Sysinfo.encode(writer);
         writer.write_int(self.main_carrier, 12);
         writer.write_int(self.frequency_band, 4);
         self.offset.encode(writer);
@@ -301,38 +304,41 @@ impl Encodable for Sysinfo {
         self.rf_parameters.encode(writer);
         self.hyperframe_or_cipher_key.encode(writer);
         self.optional_field.encode(writer);
+        writer.write(&self.tm_sdu_bits);
     }
 }
 
 impl Decodable for Sysinfo {
-    fn decode(reader: &mut Reader) -> Self {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
 
         // Decode & validate the PDU type
-        let pdu_type = DownlinkMACPDUType::decode(reader);
-        assert_eq!(pdu_type, DownlinkMACPDUType::Broadcast);
+        let pdu_type = DownlinkMACPDUType::decode(reader)?;
+        if pdu_type != DownlinkMACPDUType::Broadcast {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
 
-        let broadcast_type = BroadcastPDUType::decode(reader);
-        assert_eq!(broadcast_type, BroadcastPDUType::Sysinfo);
+        // SYSINFO is the only broadcast type, others fail to decode
+        BroadcastPDUType::decode(reader)?;
 
         let result = Sysinfo {
-            main_carrier: reader.read_int(12),
-            frequency_band: reader.read_int(4),
-            offset: Offset::decode(reader),
-            duplex_spacing: reader.read_int(3),
-            reverse: reader.read_bool(),
-            number_of_common_scch: NumberOfCommonSCCH::decode(reader),
-            rf_parameters: RFParameters::decode(reader),
-            hyperframe_or_cipher_key: HyperframeOrCipherKey::decode(reader),
-            optional_field: OptionalField::decode(reader),
+            main_carrier: reader.read_int("main_carrier", 12)?,
+            frequency_band: reader.read_int("frequency_band", 4)?,
+            offset: Offset::decode(reader)?,
+            duplex_spacing: reader.read_int("duplex_spacing", 3)?,
+            reverse: reader.read_bool("reverse")?,
+            number_of_common_scch: NumberOfCommonSCCH::decode(reader)?,
+            rf_parameters: RFParameters::decode(reader)?,
+            hyperframe_or_cipher_key: HyperframeOrCipherKey::decode(reader)?,
+            optional_field: OptionalField::decode(reader)?,
             tm_sdu_bits: Bits::new()
         };
 
-        let tm_sdu_bits = reader.read(42);
+        let tm_sdu_bits = reader.read("tm_sdu_bits", 42)?;
 
-        Sysinfo {
+        Ok(Sysinfo {
             tm_sdu_bits,
             ..result
-        }
+        })
     }
 }
 
@@ -373,6 +379,26 @@ mod tests {
 
         let mut writer = Writer::new();
         sysinfo.encode(&mut writer);
+        let bits = writer.done();
+
+        // With the PDU type prefix and the TM-SDU, this fills the BNCH exactly
+        assert_eq!(bits.len(), 124);
+
+        let decoded = Sysinfo::decode(&mut Reader::new(&bits)).unwrap();
+        assert_eq!(decoded.main_carrier, 0x123);
+        assert_eq!(decoded.tm_sdu_bits, Bits::repeat(false, 42));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn decodes_timeslot_pointer() {
+
+        let mut writer = Writer::new();
+        TimeslotPointer::InTimeslots((false, true, false, true)).encode(&mut writer);
+        let bits = writer.done();
+        assert_eq!(bits.len(), 4);
+
+        let decoded = TimeslotPointer::decode(&mut Reader::new(&bits)).unwrap();
+        assert!(matches!(decoded, TimeslotPointer::InTimeslots((false, true, false, true))));
+    }
+
+}
@@ -1,11 +1,13 @@
 use num_derive::{FromPrimitive, ToPrimitive};
-use crate::codec::SizedField;
+use crate::bits::Bits;
+use crate::codec::{remove_fill_bits, DecodeError, Reader, SizedField};
 
 #[derive(Debug, Eq, PartialEq, ToPrimitive, FromPrimitive)]
 enum DownlinkMACPDUType {
     MACResource = 0b00,
     Fragment = 0b01,
-    Broadcast = 0b10
+    Broadcast = 0b10,
+    Supplementary = 0b11
 }
 
 impl SizedField for DownlinkMACPDUType {
@@ -25,5 +27,54 @@ impl SizedField for BroadcastPDUType {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, ToPrimitive, FromPrimitive)]
+enum UplinkMACPDUType {
+    MACData = 0b00,
+    Fragment = 0b01
+}
+
+impl SizedField for UplinkMACPDUType {
+    fn size() -> usize {
+        2
+    }
+}
+
+/// Read the TM-SDU which completes a PDU starting at `pdu_start`.
+///
+/// If the length of the PDU is indicated in octets, the TM-SDU ends with the PDU, otherwise it
+/// takes the rest of the block. Blocks such as the SCH/HD and uplink subslots aren't a whole
+/// number of octets, so a PDU filling one may indicate a length up to the next octet boundary.
+/// Fill bits are removed if the PDU indicates that they're present.
+fn read_tm_sdu(
+    reader: &mut Reader,
+    pdu_start: usize,
+    length: Option<u32>,
+    fill_bit_indication: bool
+) -> Result<Bits, DecodeError> {
+
+    let header_size = reader.position() - pdu_start;
+
+    let mut tm_sdu_bits = match length {
+        Some(octets) if (octets as usize * 8) < header_size =>
+            return Err(reader.invalid_value("length_indication", octets)),
+        Some(octets) => {
+            let size = (octets as usize * 8) - header_size;
+            let block_end = reader.position() + reader.count_remaining();
+            if size > reader.count_remaining() && reader.position() + size <= block_end.next_multiple_of(8) {
+                reader.read_rest()
+            } else {
+                reader.read("tm_sdu_bits", size)?
+            }
+        },
+        None => reader.read_rest()
+    };
+
+    if fill_bit_indication {
+        remove_fill_bits(&mut tm_sdu_bits);
+    }
+
+    Ok(tm_sdu_bits)
+}
+
 pub mod uplink;
 pub mod downlink;
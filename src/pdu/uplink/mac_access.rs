@@ -1,10 +1,150 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 use super::partial::*;
+use crate::pdu::read_tm_sdu;
 
-#[derive(Debug)]
-struct MACAccess {
-    fill_bit_indication: bool,
-    encrypted: bool,
-    address: Address,
-    length_or_capacity_request: Option<LengthOrCapacityRequest>,
-    tm_sdu: Option<Vec<u8>>
+/// Size of the MAC-ACCESS length indication
+const LENGTH_SIZE: usize = 5;
+
+/// MAC-ACCESS, sent by the MS in a subslot on the SCH/HU (EN 300 392-2 § 21.4.2.2)
+#[derive(Debug, PartialEq)]
+pub struct MACAccessPDU {
+    pub fill_bit_indication: bool,
+    pub encrypted: bool,
+    pub address: Address,
+    pub length_or_capacity_request: Option<LengthOrCapacityRequest>,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACAccessPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type
+        if reader.read_bool("pdu_type")? {
+            return Err(reader.invalid_value("pdu_type", 1));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let encrypted = reader.read_bool("encrypted")?;
+        let address = Address::decode(reader)?;
+
+        let length_or_capacity_request = match reader.read_bool("optional_field_flag")? {
+            true => Some(LengthOrCapacityRequest::decode(reader, LENGTH_SIZE)?),
+            false => None
+        };
+
+        let length = length_or_capacity_request.as_ref().and_then(LengthOrCapacityRequest::length);
+
+        Ok(MACAccessPDU {
+            fill_bit_indication,
+            encrypted,
+            address,
+            length_or_capacity_request,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, length, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACAccessPDU {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_bool(false);
+        writer.write_bool(self.fill_bit_indication);
+        writer.write_bool(self.encrypted);
+        self.address.encode(writer);
+
+        match &self.length_or_capacity_request {
+            Some(length_or_capacity_request) => {
+                writer.write_bool(true);
+                length_or_capacity_request.encode(writer, LENGTH_SIZE);
+            },
+            None => writer.write_bool(false)
+        }
+
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+    use crate::codec::DecodeErrorReason;
+
+    #[test]
+    fn decodes() {
+
+        let data = from_bitstr("
+            0 // PDU type (MAC-ACCESS)
+            1 // fill bit indication
+            0 // not encrypted
+            00 // address type (SSI)
+            000000000000010000000010 // address (ten twenty six)
+            1 // optional field present
+            0 // length indication
+            00101 // length (five octets)
+            01 // tm-sdu
+            10 // fill bits
+            0000 // remainder of the subslot
+        ");
+
+        let pdu = MACAccessPDU::decode(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(pdu, MACAccessPDU {
+            fill_bit_indication: true,
+            encrypted: false,
+            address: Address::SSI { ssi: 1026 },
+            length_or_capacity_request: Some(LengthOrCapacityRequest::Length(5)),
+            tm_sdu_bits: from_bitstr("01")
+        });
+    }
+
+    #[test]
+    fn length_may_only_overrun_to_octet_boundary() {
+
+        // A whole subslot of 92 bits, which ends 4 bits into the twelfth octet
+        let subslot = |length: &str| {
+            let mut data = from_bitstr("0 0 0 00 000000000000010000000010 1 0");
+            data.extend(from_bitstr(length));
+            data.extend(Bits::repeat(true, 56));
+            data
+        };
+
+        // Twelve octets fill the subslot
+        let data = subslot("01100");
+        let pdu = MACAccessPDU::decode(&mut Reader::new(&data)).unwrap();
+        assert_eq!(pdu.tm_sdu_bits, Bits::repeat(true, 56));
+
+        // But thirteen are more than it holds
+        let data = subslot("01101");
+        let err = MACAccessPDU::decode(&mut Reader::new(&data)).unwrap_err();
+        assert_eq!(err.reason, DecodeErrorReason::InsufficientBits { required: 68, remaining: 56 });
+    }
+
+    #[test]
+    fn encodes() {
+
+        let pdu = MACAccessPDU {
+            fill_bit_indication: false,
+            encrypted: true,
+            address: Address::USSI { ussi: 0xABCDEF },
+            length_or_capacity_request: Some(LengthOrCapacityRequest::CapacityRequest {
+                fragmentation: true,
+                reservation_requirement: ReservationRequirement::Slots2
+            }),
+            tm_sdu_bits: from_bitstr("110")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("
+            0 0 1 10 101010111100110111101111 1 1 1 0010 110
+        "));
+
+        assert_eq!(MACAccessPDU::decode(&mut Reader::new(&bits)).unwrap(), pdu);
+    }
 }
@@ -0,0 +1,138 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use crate::pdu::UplinkMACPDUType;
+use super::partial::*;
+use crate::pdu::read_tm_sdu;
+
+/// Size of the MAC-DATA length indication
+const LENGTH_SIZE: usize = 6;
+
+/// MAC-DATA, sent by the MS in a full slot on the SCH/F or a half slot on the STCH
+/// (EN 300 392-2 § 21.4.2.1)
+#[derive(Debug, PartialEq)]
+pub struct MACDataPDU {
+    pub fill_bit_indication: bool,
+    pub encrypted: bool,
+    pub address: Address,
+    pub length_or_capacity_request: LengthOrCapacityRequest,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACDataPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type
+        let pdu_type = UplinkMACPDUType::decode(reader)?;
+        if pdu_type != UplinkMACPDUType::MACData {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let encrypted = reader.read_bool("encrypted")?;
+        let address = Address::decode(reader)?;
+        let length_or_capacity_request = LengthOrCapacityRequest::decode(reader, LENGTH_SIZE)?;
+        let length = length_or_capacity_request.length();
+
+        Ok(MACDataPDU {
+            fill_bit_indication,
+            encrypted,
+            address,
+            length_or_capacity_request,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, length, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACDataPDU {
+    fn encode(&self, writer: &mut Writer) {
+        UplinkMACPDUType::MACData.encode(writer);
+        writer.write_bool(self.fill_bit_indication);
+        writer.write_bool(self.encrypted);
+        self.address.encode(writer);
+        self.length_or_capacity_request.encode(writer, LENGTH_SIZE);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn decodes() {
+
+        let data = from_bitstr("
+            00 // PDU type (MAC-DATA)
+            0 // no fill bits
+            0 // not encrypted
+            01 // address type (event label)
+            0000000011 // event label
+            1 // capacity request
+            1 // fragmentation
+            0011 // reservation requirement (three slots)
+            0 // reserved
+            10110 // tm-sdu, up to the end of the block
+        ");
+
+        let pdu = MACDataPDU::decode(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(pdu, MACDataPDU {
+            fill_bit_indication: false,
+            encrypted: false,
+            address: Address::EventLabel { event_label: 3 },
+            length_or_capacity_request: LengthOrCapacityRequest::CapacityRequest {
+                fragmentation: true,
+                reservation_requirement: ReservationRequirement::Slots3
+            },
+            tm_sdu_bits: from_bitstr("10110")
+        });
+    }
+
+    #[test]
+    fn decodes_stolen_and_fragmented_lengths() {
+
+        // The TM-SDU is the rest of the block, rather than 62 or 63 octets
+        let data = from_bitstr("00 0 0 01 0000000011 0 111110 0110");
+        let pdu = MACDataPDU::decode(&mut Reader::new(&data)).unwrap();
+        assert_eq!(pdu.length_or_capacity_request, LengthOrCapacityRequest::SecondHalfSlotStolen);
+        assert_eq!(pdu.length_or_capacity_request.length(), None);
+        assert_eq!(pdu.tm_sdu_bits, from_bitstr("0110"));
+
+        let data = from_bitstr("00 0 0 01 0000000011 0 111111 0110");
+        let pdu = MACDataPDU::decode(&mut Reader::new(&data)).unwrap();
+        assert_eq!(pdu.length_or_capacity_request, LengthOrCapacityRequest::StartOfFragmentation);
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        assert_eq!(writer.done(), data);
+    }
+
+    #[test]
+    fn encodes() {
+
+        let pdu = MACDataPDU {
+            fill_bit_indication: true,
+            encrypted: false,
+            address: Address::SSI { ssi: 1026 },
+            length_or_capacity_request: LengthOrCapacityRequest::Length(5),
+            tm_sdu_bits: from_bitstr("1")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("00 1 0 00 000000000000010000000010 0 000101 1"));
+
+        // A length which is shorter than the header is rejected
+        let bits = from_bitstr("00 1 0 00 000000000000010000000010 0 000010 1");
+        assert!(matches!(
+            MACDataPDU::decode(&mut Reader::new(&bits)),
+            Err(DecodeError { field: "length_indication", .. })
+        ));
+    }
+}
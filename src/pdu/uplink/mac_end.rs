@@ -0,0 +1,97 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use crate::pdu::UplinkMACPDUType;
+use super::partial::*;
+use crate::pdu::read_tm_sdu;
+
+/// MAC-END, carrying the last fragment of a TM-SDU on the SCH/F (EN 300 392-2 § 21.4.2.5)
+#[derive(Debug, PartialEq)]
+pub struct MACEndPDU {
+    pub fill_bit_indication: bool,
+    pub length_or_reservation_requirement: LengthOrReservationRequirement,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACEndPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type and subtype
+        let pdu_type = UplinkMACPDUType::decode(reader)?;
+        if pdu_type != UplinkMACPDUType::Fragment {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
+        if !reader.read_bool("pdu_subtype")? {
+            return Err(reader.invalid_value("pdu_subtype", 0));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let length_or_reservation_requirement = LengthOrReservationRequirement::decode(reader)?;
+        let length = length_or_reservation_requirement.length();
+
+        Ok(MACEndPDU {
+            fill_bit_indication,
+            length_or_reservation_requirement,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, length, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACEndPDU {
+    fn encode(&self, writer: &mut Writer) {
+        UplinkMACPDUType::Fragment.encode(writer);
+        writer.write_bool(true);
+        writer.write_bool(self.fill_bit_indication);
+        self.length_or_reservation_requirement.encode(writer);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn decodes() {
+
+        let data = from_bitstr("
+            01 // PDU type (MAC-FRAG/MAC-END)
+            1 // subtype (MAC-END)
+            1 // fill bit indication
+            000011 // length (three octets)
+            1011001 // tm-sdu
+            1000000 // fill bits
+            11110000 // next PDU
+        ");
+
+        let pdu = MACEndPDU::decode(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(pdu, MACEndPDU {
+            fill_bit_indication: true,
+            length_or_reservation_requirement: LengthOrReservationRequirement::Length(3),
+            tm_sdu_bits: from_bitstr("1011001")
+        });
+    }
+
+    #[test]
+    fn encodes() {
+
+        let pdu = MACEndPDU {
+            fill_bit_indication: false,
+            length_or_reservation_requirement: LengthOrReservationRequirement::ReservationRequirement(
+                ReservationRequirement::Slot
+            ),
+            tm_sdu_bits: from_bitstr("0101")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("01 1 0 110001 0101"));
+        assert_eq!(MACEndPDU::decode(&mut Reader::new(&bits)).unwrap(), pdu);
+    }
+}
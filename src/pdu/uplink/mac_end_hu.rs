@@ -0,0 +1,91 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use super::partial::*;
+use crate::pdu::read_tm_sdu;
+
+/// MAC-END-HU, the last fragment of a TM-SDU sent in a subslot on the SCH/HU
+/// (EN 300 392-2 § 21.4.2.3)
+#[derive(Debug, PartialEq)]
+pub struct MACEndHUPDU {
+    pub fill_bit_indication: bool,
+    pub length_or_reservation_requirement: LengthOrReservationRequirement,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACEndHUPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type
+        if !reader.read_bool("pdu_type")? {
+            return Err(reader.invalid_value("pdu_type", 0));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+        let length_or_reservation_requirement = LengthOrReservationRequirement::decode_flagged(reader)?;
+        let length = length_or_reservation_requirement.length();
+
+        Ok(MACEndHUPDU {
+            fill_bit_indication,
+            length_or_reservation_requirement,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, length, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACEndHUPDU {
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_bool(true);
+        writer.write_bool(self.fill_bit_indication);
+        self.length_or_reservation_requirement.encode_flagged(writer);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn decodes() {
+
+        let data = from_bitstr("
+            1 // PDU type (MAC-END-HU)
+            0 // no fill bits
+            0 // length indication
+            0010 // length (two octets)
+            101100111 // tm-sdu
+            0000000 // remainder of the subslot
+        ");
+
+        let pdu = MACEndHUPDU::decode(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(pdu, MACEndHUPDU {
+            fill_bit_indication: false,
+            length_or_reservation_requirement: LengthOrReservationRequirement::Length(2),
+            tm_sdu_bits: from_bitstr("101100111")
+        });
+    }
+
+    #[test]
+    fn encodes() {
+
+        let pdu = MACEndHUPDU {
+            fill_bit_indication: false,
+            length_or_reservation_requirement: LengthOrReservationRequirement::ReservationRequirement(
+                ReservationRequirement::Slots5
+            ),
+            tm_sdu_bits: from_bitstr("0110")
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("1 0 1 0101 0110"));
+        assert_eq!(MACEndHUPDU::decode(&mut Reader::new(&bits)).unwrap(), pdu);
+    }
+}
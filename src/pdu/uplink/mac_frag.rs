@@ -0,0 +1,73 @@
+use crate::bits::Bits;
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use crate::pdu::UplinkMACPDUType;
+use crate::pdu::read_tm_sdu;
+
+/// MAC-FRAG, carrying a continuation fragment of a TM-SDU on the SCH/F (EN 300 392-2 § 21.4.2.4)
+#[derive(Debug, PartialEq)]
+pub struct MACFragPDU {
+    pub fill_bit_indication: bool,
+    pub tm_sdu_bits: Bits
+}
+
+impl Decodable for MACFragPDU {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+
+        let pdu_start = reader.position();
+
+        // Decode & validate the PDU type and subtype
+        let pdu_type = UplinkMACPDUType::decode(reader)?;
+        if pdu_type != UplinkMACPDUType::Fragment {
+            return Err(reader.invalid_value("pdu_type", pdu_type as u32));
+        }
+        if reader.read_bool("pdu_subtype")? {
+            return Err(reader.invalid_value("pdu_subtype", 1));
+        }
+
+        let fill_bit_indication = reader.read_bool("fill_bit_indication")?;
+
+        Ok(MACFragPDU {
+            fill_bit_indication,
+            tm_sdu_bits: read_tm_sdu(reader, pdu_start, None, fill_bit_indication)?
+        })
+    }
+}
+
+impl Encodable for MACFragPDU {
+    fn encode(&self, writer: &mut Writer) {
+        UplinkMACPDUType::Fragment.encode(writer);
+        writer.write_bool(false);
+        writer.write_bool(self.fill_bit_indication);
+        writer.write(&self.tm_sdu_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn decodes() {
+
+        let data = from_bitstr("
+            01 // PDU type (MAC-FRAG/MAC-END)
+            0 // subtype (MAC-FRAG)
+            1 // fill bit indication
+            110010 // tm-sdu
+            1000 // fill bits
+        ");
+
+        let pdu = MACFragPDU::decode(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(pdu, MACFragPDU {
+            fill_bit_indication: true,
+            tm_sdu_bits: from_bitstr("110010")
+        });
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        assert_eq!(writer.done(), from_bitstr("01 0 1 110010"));
+    }
+}
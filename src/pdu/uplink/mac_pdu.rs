@@ -0,0 +1,79 @@
+use crate::bits::Bits;
+use crate::codec::{Decodable, DecodeError, Reader};
+use crate::pdu::UplinkMACPDUType;
+use crate::pdu::uplink::{MACAccessPDU, MACDataPDU, MACEndHUPDU, MACEndPDU, MACFragPDU};
+
+/// An uplink MAC PDU, as carried at the start of a MAC block on the SCH/HU, SCH/F or STCH
+#[derive(Debug)]
+pub enum UplinkMACPDU {
+    MACAccess(MACAccessPDU),
+    MACEndHU(MACEndHUPDU),
+    MACData(MACDataPDU),
+    MACFrag(MACFragPDU),
+    MACEnd(MACEndPDU),
+    /// A PDU which cannot yet be decoded, holding the whole MAC block
+    Unsupported(Bits)
+}
+
+impl UplinkMACPDU {
+
+    /// Parse the PDU at the start of a MAC block sent in a subslot (SCH/HU), which has a 1-bit
+    /// PDU type
+    pub fn parse_subslot(block: &Bits) -> Result<UplinkMACPDU, DecodeError> {
+
+        let mut reader = Reader::new(block);
+
+        Ok(match reader.read_bool("pdu_type")? {
+            false => UplinkMACPDU::MACAccess(MACAccessPDU::decode(&mut Reader::new(block))?),
+            true => UplinkMACPDU::MACEndHU(MACEndHUPDU::decode(&mut Reader::new(block))?)
+        })
+    }
+
+    /// Parse the PDU at the start of a MAC block sent in a full slot (SCH/F) or half slot (STCH),
+    /// based on its PDU type
+    pub fn parse(block: &Bits) -> Result<UplinkMACPDU, DecodeError> {
+
+        let mut reader = Reader::new(block);
+        let pdu_type: Option<UplinkMACPDUType> = num::FromPrimitive::from_u32(reader.read_int("pdu_type", 2)?);
+
+        Ok(match pdu_type {
+            Some(UplinkMACPDUType::MACData) =>
+                UplinkMACPDU::MACData(MACDataPDU::decode(&mut Reader::new(block))?),
+            Some(UplinkMACPDUType::Fragment) => match reader.read_bool("pdu_subtype")? {
+                false => UplinkMACPDU::MACFrag(MACFragPDU::decode(&mut Reader::new(block))?),
+                true => UplinkMACPDU::MACEnd(MACEndPDU::decode(&mut Reader::new(block))?)
+            },
+            None => UplinkMACPDU::Unsupported(block.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn parses_by_pdu_type() {
+
+        let block = from_bitstr("0000 000000000000000000000000 0 0000");
+        assert!(matches!(UplinkMACPDU::parse_subslot(&block), Ok(UplinkMACPDU::MACAccess(_))));
+
+        let block = from_bitstr("0000 00 000000000000000000000000 1 000000");
+        assert!(matches!(UplinkMACPDU::parse(&block), Ok(UplinkMACPDU::MACData(_))));
+
+        let block = from_bitstr("1000010000000000");
+        assert!(matches!(UplinkMACPDU::parse_subslot(&block), Ok(UplinkMACPDU::MACEndHU(_))));
+
+        let block = from_bitstr("0100000000000000");
+        assert!(matches!(UplinkMACPDU::parse(&block), Ok(UplinkMACPDU::MACFrag(_))));
+
+        let block = from_bitstr("0110000010000000");
+        assert!(matches!(UplinkMACPDU::parse(&block), Ok(UplinkMACPDU::MACEnd(_))));
+
+        // MAC-U-SIGNAL isn't supported yet
+        let block = from_bitstr("1100000000000000");
+        assert!(matches!(UplinkMACPDU::parse(&block), Ok(UplinkMACPDU::Unsupported(_))));
+    }
+}
@@ -4,4 +4,13 @@ mod mac_access;
 mod mac_end;
 mod mac_frag;
 mod mac_data;
-mod mac_end_hu;
\ No newline at end of file
+mod mac_end_hu;
+mod mac_pdu;
+
+pub use partial::*;
+pub use mac_access::*;
+pub use mac_end::*;
+pub use mac_frag::*;
+pub use mac_data::*;
+pub use mac_end_hu::*;
+pub use mac_pdu::UplinkMACPDU;
@@ -1,7 +1,66 @@
-#[derive(Debug)]
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum Address {
     SSI { ssi: u32 },
+    EventLabel { event_label: u32 },
     USSI { ussi: u32 },
-    SMI {ssi: u32 } ,
-    EventLabel { event_label: u32 }
+    SMI { smi: u32 }
+}
+
+impl Address {
+    fn get_type(&self) -> u32 {
+        match self {
+            Address::SSI { .. } => 0b00,
+            Address::EventLabel { .. } => 0b01,
+            Address::USSI { .. } => 0b10,
+            Address::SMI { .. } => 0b11
+        }
+    }
+}
+
+impl Decodable for Address {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(match reader.read_int("address_type", 2)? {
+            0b00 => Address::SSI { ssi: reader.read_int("ssi", 24)? },
+            0b01 => Address::EventLabel { event_label: reader.read_int("event_label", 10)? },
+            0b10 => Address::USSI { ussi: reader.read_int("ussi", 24)? },
+            _ => Address::SMI { smi: reader.read_int("smi", 24)? }
+        })
+    }
+}
+
+impl Encodable for Address {
+    fn encode(&self, writer: &mut Writer) {
+
+        // Write the type
+        writer.write_int(self.get_type(), 2);
+
+        // Write the content
+        match self {
+            Self::SSI { ssi } => writer.write_int(*ssi, 24),
+            Self::EventLabel { event_label } => writer.write_int(*event_label, 10),
+            Self::USSI { ussi } => writer.write_int(*ussi, 24),
+            Self::SMI { smi } => writer.write_int(*smi, 24)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::from_bitstr;
+
+    #[test]
+    fn encodes_and_decodes() {
+
+        let address = Address::EventLabel { event_label: 0b1000000001 };
+        let mut writer = Writer::new();
+        address.encode(&mut writer);
+        let bits = writer.done();
+
+        assert_eq!(bits, from_bitstr("01 1000000001"));
+        assert_eq!(Address::decode(&mut Reader::new(&bits)), Ok(address));
+    }
 }
@@ -1,10 +1,92 @@
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
 use super::reservation_requirement::ReservationRequirement;
 
-#[derive(Debug)]
+/// Size of the MAC-DATA length indication, the only one which reserves values to indicate
+/// stealing or fragmentation
+const STEALING_LENGTH_SIZE: usize = 6;
+
+/// Length indication when the second half of the slot is stolen
+const SECOND_HALF_SLOT_STOLEN: u32 = 0b111110;
+
+/// Length indication at the start of fragmentation
+const START_OF_FRAGMENTATION: u32 = 0b111111;
+
+/// Length indication or capacity request, as carried by MAC-ACCESS and MAC-DATA.
+/// The size of the length indication differs between the two, and a capacity request is padded
+/// with reserved bits to the same size.
+#[derive(Debug, PartialEq, Eq)]
 pub enum LengthOrCapacityRequest {
-    Length,
+    /// Length of the MAC PDU in octets
+    Length(u32),
+    /// The second half of the slot is stolen, and the MAC PDU fills the first (MAC-DATA only)
+    SecondHalfSlotStolen,
+    /// The MAC PDU is the start of a fragmented TM-SDU, and fills the rest of the block
+    /// (MAC-DATA only)
+    StartOfFragmentation,
     CapacityRequest {
         fragmentation: bool,
         reservation_requirement: ReservationRequirement
     }
 }
+
+impl LengthOrCapacityRequest {
+
+    /// Decode, following the length indication or capacity request flag
+    pub fn decode(reader: &mut Reader, length_size: usize) -> Result<Self, DecodeError> {
+
+        if !reader.read_bool("length_or_capacity_request")? {
+            let length = reader.read_int("length_indication", length_size)?;
+            return Ok(match (length_size, length) {
+                (STEALING_LENGTH_SIZE, SECOND_HALF_SLOT_STOLEN) => LengthOrCapacityRequest::SecondHalfSlotStolen,
+                (STEALING_LENGTH_SIZE, START_OF_FRAGMENTATION) => LengthOrCapacityRequest::StartOfFragmentation,
+                _ => LengthOrCapacityRequest::Length(length)
+            });
+        }
+
+        let capacity_request = LengthOrCapacityRequest::CapacityRequest {
+            fragmentation: reader.read_bool("fragmentation")?,
+            reservation_requirement: ReservationRequirement::decode(reader)?
+        };
+        reader.skip("reserved", length_size - 5)?;
+
+        Ok(capacity_request)
+    }
+
+    /// Encode, including the length indication or capacity request flag
+    pub fn encode(&self, writer: &mut Writer, length_size: usize) {
+        match self {
+            LengthOrCapacityRequest::Length(length) => {
+                writer.write_bool(false);
+                writer.write_int(*length, length_size);
+            },
+            LengthOrCapacityRequest::SecondHalfSlotStolen => {
+                writer.write_bool(false);
+                writer.write_int(SECOND_HALF_SLOT_STOLEN, length_size);
+            },
+            LengthOrCapacityRequest::StartOfFragmentation => {
+                writer.write_bool(false);
+                writer.write_int(START_OF_FRAGMENTATION, length_size);
+            },
+            LengthOrCapacityRequest::CapacityRequest { fragmentation, reservation_requirement } => {
+                writer.write_bool(true);
+                writer.write_bool(*fragmentation);
+                reservation_requirement.encode(writer);
+
+                // Reserved
+                if length_size > 5 {
+                    writer.write_int(0, length_size - 5);
+                }
+            }
+        }
+    }
+
+    /// The length of the MAC PDU, if indicated
+    pub fn length(&self) -> Option<u32> {
+        match self {
+            LengthOrCapacityRequest::Length(length) => Some(*length),
+            LengthOrCapacityRequest::SecondHalfSlotStolen |
+            LengthOrCapacityRequest::StartOfFragmentation |
+            LengthOrCapacityRequest::CapacityRequest { .. } => None
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::codec::{Reader, Decodable, Encodable, Writer, DecodeError};
+use super::reservation_requirement::ReservationRequirement;
+
+/// Length indication or reservation requirement, as carried by MAC-END and MAC-END-HU
+#[derive(Debug, PartialEq, Eq)]
+pub enum LengthOrReservationRequirement {
+    /// Length of the MAC PDU in octets
+    Length(u32),
+    ReservationRequirement(ReservationRequirement)
+}
+
+impl LengthOrReservationRequirement {
+
+    /// Decode the 4-bit length indication or reservation requirement of a MAC-END-HU, following
+    /// its flag
+    pub fn decode_flagged(reader: &mut Reader) -> Result<Self, DecodeError> {
+        if reader.read_bool("length_or_capacity_request")? {
+            Ok(LengthOrReservationRequirement::ReservationRequirement(ReservationRequirement::decode(reader)?))
+        } else {
+            Ok(LengthOrReservationRequirement::Length(reader.read_int("length_indication", 4)?))
+        }
+    }
+
+    /// Encode as the 4-bit length indication or reservation requirement of a MAC-END-HU,
+    /// including its flag
+    pub fn encode_flagged(&self, writer: &mut Writer) {
+        match self {
+            LengthOrReservationRequirement::Length(length) => {
+                writer.write_bool(false);
+                writer.write_int(*length, 4);
+            },
+            LengthOrReservationRequirement::ReservationRequirement(reservation_requirement) => {
+                writer.write_bool(true);
+                reservation_requirement.encode(writer);
+            }
+        }
+    }
+
+    /// The length of the MAC PDU, if indicated
+    pub fn length(&self) -> Option<u32> {
+        match self {
+            LengthOrReservationRequirement::Length(length) => Some(*length),
+            LengthOrReservationRequirement::ReservationRequirement(_) => None
+        }
+    }
+
+    /// The reservation requirement, if one is carried instead of the length
+    pub fn reservation_requirement(&self) -> Option<ReservationRequirement> {
+        match self {
+            LengthOrReservationRequirement::Length(_) => None,
+            LengthOrReservationRequirement::ReservationRequirement(requirement) => Some(*requirement)
+        }
+    }
+}
+
+/// The 6-bit field of a MAC-END, where values from 0b110000 carry a reservation requirement
+impl Decodable for LengthOrReservationRequirement {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        match reader.read_int("length_or_reservation_requirement", 6)? {
+            requirement @ 0b110000..=0b111111 => Ok(LengthOrReservationRequirement::ReservationRequirement(
+                num::FromPrimitive::from_u32(requirement & 0b1111).unwrap()
+            )),
+            length => Ok(LengthOrReservationRequirement::Length(length))
+        }
+    }
+}
+
+impl Encodable for LengthOrReservationRequirement {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            LengthOrReservationRequirement::Length(length) => writer.write_int(*length, 6),
+            LengthOrReservationRequirement::ReservationRequirement(reservation_requirement) => {
+                writer.write_int(0b11, 2);
+                reservation_requirement.encode(writer);
+            }
+        }
+    }
+}
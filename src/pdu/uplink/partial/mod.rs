@@ -2,7 +2,10 @@
 
 pub use address::Address;
 pub use length_or_capacity_request::LengthOrCapacityRequest;
+pub use length_or_reservation_requirement::LengthOrReservationRequirement;
+pub use reservation_requirement::ReservationRequirement;
 
 mod length_or_capacity_request;
+mod length_or_reservation_requirement;
 mod address;
-mod reservation_requirement;
+mod reservation_requirement;
\ No newline at end of file
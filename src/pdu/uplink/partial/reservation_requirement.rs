@@ -1,6 +1,9 @@
-#[derive(Debug)]
+use num_derive::{FromPrimitive, ToPrimitive};
+use crate::codec::SizedField;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive)]
 pub enum ReservationRequirement {
-    Subslot = 0b000,
+    Subslot = 0b0000,
     Slot = 0b0001,
     Slots2 = 0b0010,
     Slots3 = 0b0011,
@@ -17,3 +20,34 @@ pub enum ReservationRequirement {
     Slots68 = 0b1110,
     MoreThan68Slots = 0b1111
 }
+
+impl ReservationRequirement {
+
+    /// The capacity required, in subslots. More than 68 slots is taken to be 69.
+    pub fn subslots(&self) -> u32 {
+        match self {
+            ReservationRequirement::Subslot => 1,
+            ReservationRequirement::Slot => 2,
+            ReservationRequirement::Slots2 => 4,
+            ReservationRequirement::Slots3 => 6,
+            ReservationRequirement::Slots4 => 8,
+            ReservationRequirement::Slots5 => 10,
+            ReservationRequirement::Slots6 => 12,
+            ReservationRequirement::Slots8 => 16,
+            ReservationRequirement::Slots10 => 20,
+            ReservationRequirement::Slots13 => 26,
+            ReservationRequirement::Slots17 => 34,
+            ReservationRequirement::Slots24 => 48,
+            ReservationRequirement::Slots34 => 68,
+            ReservationRequirement::Slots51 => 102,
+            ReservationRequirement::Slots68 => 136,
+            ReservationRequirement::MoreThan68Slots => 138
+        }
+    }
+}
+
+impl SizedField for ReservationRequirement {
+    fn size() -> usize {
+        4
+    }
+}
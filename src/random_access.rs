@@ -0,0 +1,423 @@
+use crate::bits::Bits;
+use crate::codec::{add_fill_bits, Encodable, FillBitCapacity, Writer};
+use crate::pdu::downlink::{AccessAssign, AccessCode, AccessCodeDefinition, AccessField, BaseFrameLength, Immediate};
+use crate::pdu::uplink::{Address, MACAccessPDU, UplinkMACPDU};
+use crate::tdma_time::TDMATime;
+
+/// Capacity of a MAC block on the SCH/HU
+const SUBSLOT_CAPACITY: usize = 92;
+
+/// Whether a subslot may be used for random access with access code A
+fn is_valid_subslot(access_field: Option<&AccessField>) -> bool {
+    access_field.is_some_and(|field| {
+        field.access_code == AccessCode::AccessCodeA && !matches!(
+            field.base_frame_length,
+            BaseFrameLength::ReservedSubslot | BaseFrameLength::CLCHSubslot
+        )
+    })
+}
+
+/// A small xorshift generator, so that simulations are repeatable from a seed
+struct Rng(u64);
+
+impl Rng {
+
+    fn new(seed: u64) -> Self {
+        // The state must never be zero
+        Rng(seed.wrapping_mul(0x9E3779B97F4A7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A uniformly distributed value in `0..n`
+    fn below(&mut self, n: u32) -> u32 {
+        (self.next() % n as u64) as u32
+    }
+
+    /// True with probability `p`
+    fn chance(&mut self, p: f64) -> bool {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64 <= p
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum RandomAccessState {
+    /// No request to send
+    Idle,
+    /// Sending in the first valid subslot, if one arrives soon enough after the request
+    Immediate { requested: TDMATime },
+    /// Waiting for an access frame to start, to choose a subslot in
+    AwaitingAccessFrame,
+    /// Counting down the valid subslots before the one chosen
+    Deferred { subslots: u32 },
+    /// Waiting for the BS to respond to the request
+    AwaitingResponse { opportunities: u32 },
+    Succeeded { attempts: u32 },
+    Failed
+}
+
+/// The MS side of the random access protocol for access code A, according to ETSI
+/// EN 300 392-2 § 23.5.1
+///
+/// A new request may be sent immediately, depending on the IMM parameter. Otherwise, and for every
+/// retry, the MS waits for an access frame to start and sends in a subslot chosen at random from
+/// it. If the BS doesn't respond within WT downlink signalling opportunities, the request is
+/// repeated, up to Nu attempts in total. Only subslots which the AACH marks for access code A,
+/// and doesn't reserve, count towards the access frame.
+pub(crate) struct RandomAccessMS {
+    ssi: u32,
+    definition: AccessCodeDefinition,
+    state: RandomAccessState,
+    attempts: u32,
+    rng: Rng
+}
+
+impl RandomAccessMS {
+
+    pub(crate) fn new(ssi: u32, definition: AccessCodeDefinition, seed: u64) -> Self {
+        RandomAccessMS {
+            ssi,
+            definition,
+            state: RandomAccessState::Idle,
+            attempts: 0,
+            rng: Rng::new(seed)
+        }
+    }
+
+    pub(crate) fn state(&self) -> &RandomAccessState {
+        &self.state
+    }
+
+    /// Start a new request at `time`
+    pub(crate) fn request(&mut self, time: &TDMATime) {
+        self.attempts = 0;
+        self.state = match self.definition.immediate {
+            Immediate::AlwaysRandomise => RandomAccessState::AwaitingAccessFrame,
+            _ => RandomAccessState::Immediate { requested: time.clone() }
+        };
+    }
+
+    /// Handle an uplink subslot at `time`, described by its access field from the AACH. Returns
+    /// the MAC block to send in the subslot, if the MS sends its request.
+    pub(crate) fn subslot(&mut self, access_field: Option<&AccessField>, time: &TDMATime) -> Option<Bits> {
+
+        let valid = is_valid_subslot(access_field);
+
+        if let RandomAccessState::Immediate { requested } = &self.state {
+            let expired = match self.definition.immediate {
+                Immediate::AfterFrames(frames) => time.slots_since(requested) >= frames * 4,
+                _ => false
+            };
+            if valid && !expired {
+                return Some(self.send());
+            }
+            if expired {
+                self.state = RandomAccessState::AwaitingAccessFrame;
+            }
+        }
+
+        // Choose a subslot from the access frame starting with this subslot
+        if self.state == RandomAccessState::AwaitingAccessFrame {
+            if let Some(subslots) = access_field
+                .filter(|field| field.access_code == AccessCode::AccessCodeA)
+                .and_then(|field| field.base_frame_length.subslots()) {
+                let subslots = match self.definition.frame_length_x4 {
+                    true => subslots * 4,
+                    false => subslots
+                };
+                self.state = RandomAccessState::Deferred { subslots: self.rng.below(subslots) };
+            }
+        }
+
+        if let RandomAccessState::Deferred { subslots } = &mut self.state {
+            if valid {
+                if *subslots == 0 {
+                    return Some(self.send());
+                }
+                *subslots -= 1;
+            }
+        }
+
+        None
+    }
+
+    /// Handle a downlink signalling opportunity, which may acknowledge the request
+    pub(crate) fn downlink(&mut self, acknowledged: bool) {
+
+        let RandomAccessState::AwaitingResponse { opportunities } = &mut self.state else {
+            return;
+        };
+
+        if acknowledged {
+            self.state = RandomAccessState::Succeeded { attempts: self.attempts };
+            return;
+        }
+
+        *opportunities -= 1;
+        if *opportunities == 0 {
+            self.state = match self.attempts >= self.definition.number_of_attempts.max(1) {
+                true => RandomAccessState::Failed,
+                false => RandomAccessState::AwaitingAccessFrame
+            };
+        }
+    }
+
+    /// Send the request as a MAC-ACCESS PDU filling the subslot
+    fn send(&mut self) -> Bits {
+
+        self.attempts += 1;
+        self.state = RandomAccessState::AwaitingResponse {
+            opportunities: self.definition.waiting_time_opportunities.max(1)
+        };
+
+        let pdu = MACAccessPDU {
+            fill_bit_indication: true,
+            encrypted: false,
+            address: Address::SSI { ssi: self.ssi },
+            length_or_capacity_request: None,
+            tm_sdu_bits: Bits::new()
+        };
+
+        let mut writer = Writer::new();
+        pdu.encode(&mut writer);
+        let mut bits = writer.done();
+        add_fill_bits(&mut bits, FillBitCapacity::Bits(SUBSLOT_CAPACITY));
+        bits
+    }
+}
+
+/// The BS side of random access. A subslot carrying a single request is received, but requests
+/// sent in the same subslot collide and are all lost.
+#[derive(Debug, Default)]
+pub(crate) struct RandomAccessBS {
+    pub(crate) received: u32,
+    pub(crate) collisions: u32
+}
+
+impl RandomAccessBS {
+
+    /// Receive the MAC blocks sent in a subslot, returning the SSI of the MS whose request was
+    /// received, if any
+    pub(crate) fn subslot(&mut self, blocks: &[Bits]) -> Option<u32> {
+
+        let block = match blocks {
+            [] => return None,
+            [block] => block,
+            _ => {
+                self.collisions += 1;
+                return None;
+            }
+        };
+
+        match UplinkMACPDU::parse_subslot(block) {
+            Ok(UplinkMACPDU::MACAccess(MACAccessPDU { address: Address::SSI { ssi }, .. })) => {
+                self.received += 1;
+                Some(ssi)
+            },
+            _ => None
+        }
+    }
+}
+
+/// Results of a random access simulation
+#[derive(Debug, Default)]
+pub(crate) struct RandomAccessResults {
+    pub(crate) requests: u32,
+    pub(crate) successes: u32,
+    pub(crate) failures: u32,
+    pub(crate) collisions: u32,
+    /// For each successful request, the number of frames from the request to its acknowledgement
+    pub(crate) latencies: Vec<u32>
+}
+
+impl RandomAccessResults {
+
+    /// The proportion of completed requests which succeeded
+    pub(crate) fn success_rate(&self) -> f64 {
+        self.successes as f64 / (self.successes + self.failures).max(1) as f64
+    }
+
+    /// The mean number of frames taken by a successful request
+    pub(crate) fn mean_latency(&self) -> f64 {
+        self.latencies.iter().sum::<u32>() as f64 / self.latencies.len().max(1) as f64
+    }
+}
+
+/// A random access simulation on the MCCH, which is timeslot 1
+pub(crate) struct RandomAccessSimulation {
+    /// The number of MSs contending for access
+    pub(crate) ms_count: u32,
+    /// The probability of an idle MS making a request in each frame
+    pub(crate) request_probability: f64,
+    /// The number of TDMA frames to simulate
+    pub(crate) frames: u32,
+    pub(crate) seed: u64
+}
+
+impl RandomAccessSimulation {
+
+    /// Run the simulation, with the access parameters broadcast in SYSINFO and the AACH produced
+    /// by `access_assign` for each slot. Requests received by the BS are acknowledged on the
+    /// downlink in the following frame.
+    pub(crate) fn run(&self, definition: &AccessCodeDefinition, access_assign: impl Fn(&TDMATime) -> AccessAssign) -> RandomAccessResults {
+
+        let mut rng = Rng::new(self.seed);
+        let mut mss: Vec<RandomAccessMS> = (0..self.ms_count)
+            .map(|ssi| RandomAccessMS::new(ssi + 1, definition.clone(), rng.next()))
+            .collect();
+        let mut requested_frames = vec![0; mss.len()];
+
+        let mut bs = RandomAccessBS::default();
+        let mut results = RandomAccessResults::default();
+
+        for frame in 0..self.frames {
+
+            let time = TDMATime::at(0, frame % 18, (frame / 18) % 60, frame / (18 * 60));
+
+            // New requests, including from MSs which have finished their previous one
+            for (index, ms) in mss.iter_mut().enumerate() {
+                if matches!(ms.state(), RandomAccessState::Idle | RandomAccessState::Succeeded { .. } | RandomAccessState::Failed)
+                    && rng.chance(self.request_probability) {
+                    ms.request(&time);
+                    requested_frames[index] = frame;
+                    results.requests += 1;
+                }
+            }
+
+            // Both uplink subslots of the slot
+            let access_assign = access_assign(&time);
+            let mut acknowledged = vec![];
+            for access_field in access_assign.access_fields() {
+                let blocks: Vec<Bits> = mss.iter_mut()
+                    .filter_map(|ms| ms.subslot(access_field, &time))
+                    .collect();
+                acknowledged.extend(bs.subslot(&blocks));
+            }
+
+            // Responses in the next downlink slot
+            for (index, ms) in mss.iter_mut().enumerate() {
+                ms.downlink(acknowledged.contains(&ms.ssi));
+                match ms.state() {
+                    RandomAccessState::Succeeded { .. } => {
+                        results.successes += 1;
+                        results.latencies.push(frame + 1 - requested_frames[index]);
+                        ms.state = RandomAccessState::Idle;
+                    },
+                    RandomAccessState::Failed => {
+                        results.failures += 1;
+                        ms.state = RandomAccessState::Idle;
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        results.collisions = bs.collisions;
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::pdu::downlink::{AccessAssignNormalFrame, TimeslotPointer};
+
+    fn definition(immediate: Immediate) -> AccessCodeDefinition {
+        AccessCodeDefinition {
+            immediate,
+            waiting_time_opportunities: 2,
+            number_of_attempts: 3,
+            frame_length_x4: false,
+            timeslot: TimeslotPointer::SameAsDownlink,
+            minimum_priority: 0
+        }
+    }
+
+    /// An access frame of four subslots starts every other frame
+    fn access_assign(time: &TDMATime) -> AccessAssign {
+        let access_field = |base_frame_length| AccessField {
+            access_code: AccessCode::AccessCodeA,
+            base_frame_length
+        };
+        AccessAssign::NormalFrame(AccessAssignNormalFrame::DownlinkCommonUplinkCommon {
+            access_field_1: access_field(match time.frame() % 2 {
+                1 => BaseFrameLength::Subslots4,
+                _ => BaseFrameLength::OngoingFrame
+            }),
+            access_field_2: access_field(BaseFrameLength::OngoingFrame)
+        })
+    }
+
+    #[test]
+    fn sends_immediately_then_retries_until_failure() {
+
+        let time = TDMATime::at(0, 1, 0, 0);
+        let mut ms = RandomAccessMS::new(1026, definition(Immediate::Immediate), 1);
+        ms.request(&time);
+
+        // The request is received by the BS
+        let field = AccessField { access_code: AccessCode::AccessCodeA, base_frame_length: BaseFrameLength::OngoingFrame };
+        let block = ms.subslot(Some(&field), &time).unwrap();
+        assert_eq!(RandomAccessBS::default().subslot(&[block]), Some(1026));
+
+        // Retries are randomised over access frames, until the attempts run out
+        let mut sent = 1;
+        let mut frame = 2;
+        while *ms.state() != RandomAccessState::Failed {
+            let time = TDMATime::at(0, frame % 18, frame / 18, 0);
+            for access_field in access_assign(&time).access_fields() {
+                sent += ms.subslot(access_field, &time).iter().count();
+            }
+            ms.downlink(false);
+            frame += 1;
+        }
+        assert_eq!(sent, 3);
+    }
+
+    #[test]
+    fn collides_requests_in_the_same_subslot() {
+
+        let time = TDMATime::at(0, 1, 0, 0);
+        let field = AccessField { access_code: AccessCode::AccessCodeA, base_frame_length: BaseFrameLength::OngoingFrame };
+        let blocks: Vec<Bits> = [1, 2].into_iter()
+            .filter_map(|ssi| {
+                let mut ms = RandomAccessMS::new(ssi, definition(Immediate::Immediate), ssi as u64);
+                ms.request(&time);
+                ms.subslot(Some(&field), &time)
+            })
+            .collect();
+
+        let mut bs = RandomAccessBS::default();
+        assert_eq!(bs.subslot(&blocks), None);
+        assert_eq!((bs.received, bs.collisions), (0, 1));
+    }
+
+    #[test]
+    fn simulates_access_under_load() {
+
+        let simulation = |ms_count| RandomAccessSimulation {
+            ms_count,
+            request_probability: 0.05,
+            frames: 18 * 60,
+            seed: 42
+        }.run(&definition(Immediate::AlwaysRandomise), access_assign);
+
+        // A single MS never collides
+        let results = simulation(1);
+        assert!(results.requests > 0);
+        assert_eq!(results.collisions, 0);
+        assert_eq!(results.success_rate(), 1.0);
+
+        // Many contend for too few subslots
+        let results = simulation(40);
+        assert!(results.collisions > 0);
+        assert!(results.success_rate() < 1.0);
+        assert!(results.mean_latency() > simulation(1).mean_latency());
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+use crate::codec::Optional;
+use crate::pdu::downlink::{self, CapacityAllocation, GrantingDelay, MACResourcePDU, SlotGranting, ALLOCATABLE_SLOTS};
+use crate::pdu::uplink::{Address, LengthOrCapacityRequest, ReservationRequirement, UplinkMACPDU};
+use crate::tdma_time::TDMATime;
+
+/// Size of a MAC-RESOURCE PDU addressed by SSI which only grants capacity
+pub(crate) const GRANT_PDU_SIZE: usize = 56;
+
+/// The longest granting delay, in opportunities
+const MAX_GRANTING_DELAY: u32 = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Capacity {
+    /// The first (0) or second (1) subslot of a slot
+    Subslot(usize),
+    /// Whole slots, in consecutive frames
+    Slots(u32)
+}
+
+struct Reservation {
+    ssi: u32,
+    first_frame: u32,
+    capacity: Capacity
+}
+
+impl Reservation {
+
+    fn covers(&self, frame: u32, subslot: usize) -> bool {
+        match self.capacity {
+            Capacity::Subslot(reserved) => frame == self.first_frame && subslot == reserved,
+            Capacity::Slots(slots) => (self.first_frame..self.first_frame + slots).contains(&frame)
+        }
+    }
+
+    fn last_frame(&self) -> u32 {
+        match self.capacity {
+            Capacity::Subslot(_) => self.first_frame,
+            Capacity::Slots(slots) => self.first_frame + slots - 1
+        }
+    }
+}
+
+/// Number of the TDMA frame containing `time`, counting from the start of the first hyperframe
+fn frame_number(time: &TDMATime) -> u32 {
+    time.slots_since(&TDMATime::new()) / 4
+}
+
+/// The reservation requirement of a capacity request, if it is one
+fn capacity_request(length_or_capacity_request: Option<&LengthOrCapacityRequest>) -> Option<ReservationRequirement> {
+    match length_or_capacity_request {
+        Some(LengthOrCapacityRequest::CapacityRequest { reservation_requirement, .. }) => Some(*reservation_requirement),
+        _ => None
+    }
+}
+
+/// Reserves uplink capacity on the timeslot of the MCCH for MSs which request it, according to
+/// ETSI EN 300 392-2 § 23.5.2
+///
+/// Capacity granted in a downlink slot starts at the next opportunity, the uplink slot of the
+/// same timeslot in the following frame, unless delayed by the granting delay. Requests are
+/// granted in the order received, as soon as any capacity is free. Only numbers of slots the
+/// capacity allocation can express are granted, the most which fit both the request and the free
+/// capacity, so the rest of a request may be granted in several parts.
+pub(crate) struct ReservationManager {
+    /// SSI and outstanding requirement in subslots of each MS, in the order requested
+    requests: VecDeque<(u32, u32)>,
+    reservations: Vec<Reservation>
+}
+
+impl ReservationManager {
+
+    pub(crate) fn new() -> Self {
+        ReservationManager {
+            requests: VecDeque::new(),
+            reservations: vec![]
+        }
+    }
+
+    /// Record a request for capacity, replacing any outstanding for the MS
+    pub(crate) fn request(&mut self, ssi: u32, requirement: ReservationRequirement) {
+        match self.requests.iter_mut().find(|(requester, _)| *requester == ssi) {
+            Some(request) => request.1 = requirement.subslots(),
+            None => self.requests.push_back((ssi, requirement.subslots()))
+        }
+    }
+
+    /// Whether any requests are waiting to be granted
+    pub(crate) fn has_pending_requests(&self) -> bool {
+        !self.requests.is_empty()
+    }
+
+    /// Record the capacity request carried by a PDU received at `time` in `subslot` (0 for a full
+    /// slot). MAC-END and MAC-END-HU carry no address, so they're attributed to the MS which the
+    /// capacity they were sent in was reserved for.
+    pub(crate) fn receive(&mut self, pdu: &UplinkMACPDU, time: &TDMATime, subslot: usize) {
+
+        let (address, requirement) = match pdu {
+            UplinkMACPDU::MACAccess(pdu) =>
+                (Some(&pdu.address), capacity_request(pdu.length_or_capacity_request.as_ref())),
+            UplinkMACPDU::MACData(pdu) =>
+                (Some(&pdu.address), capacity_request(Some(&pdu.length_or_capacity_request))),
+            UplinkMACPDU::MACEndHU(pdu) => (None, pdu.length_or_reservation_requirement.reservation_requirement()),
+            UplinkMACPDU::MACEnd(pdu) => (None, pdu.length_or_reservation_requirement.reservation_requirement()),
+            _ => return
+        };
+
+        let ssi = match address {
+            Some(Address::SSI { ssi }) => Some(*ssi),
+            Some(_) => None,
+            None => self.reserved_for(time, subslot)
+        };
+
+        if let (Some(ssi), Some(requirement)) = (ssi, requirement) {
+            self.request(ssi, requirement);
+        }
+    }
+
+    /// The SSI of the MS which `subslot` of the uplink slot at `time` is reserved for, if any
+    pub(crate) fn reserved_for(&self, time: &TDMATime, subslot: usize) -> Option<u32> {
+        let frame = frame_number(time);
+        self.reservations.iter()
+            .find(|reservation| reservation.covers(frame, subslot))
+            .map(|reservation| reservation.ssi)
+    }
+
+    fn is_reserved(&self, frame: u32, subslot: usize) -> bool {
+        self.reservations.iter().any(|reservation| reservation.covers(frame, subslot))
+    }
+
+    /// Reserve capacity for the longest waiting request, returning the MAC-RESOURCE PDU granting
+    /// it, to be sent in the downlink slot at `time`. Returns `None` if there's no request, or no
+    /// capacity free within the longest granting delay.
+    ///
+    /// Subslots for which `unavailable` is true, given the time of the slot and the subslot, are
+    /// never reserved, such as those offered for the CLCH.
+    pub(crate) fn grant(
+        &mut self,
+        time: &TDMATime,
+        unavailable: impl Fn(&TDMATime, usize) -> bool
+    ) -> Option<MACResourcePDU> {
+
+        let &(ssi, subslots) = self.requests.front()?;
+        let now = frame_number(time);
+        let next_opportunity = now + 1;
+
+        // Forget reservations which have passed
+        self.reservations.retain(|reservation| reservation.last_frame() >= next_opportunity);
+
+        let is_free = |frame: u32, subslot: usize| {
+            !self.is_reserved(frame, subslot) && !unavailable(&time.plus_frames(frame - now), subslot)
+        };
+
+        let (delay, first_frame, capacity) = (0..=MAX_GRANTING_DELAY).find_map(|delay| {
+            let first_frame = next_opportunity + delay;
+            let capacity = match subslots {
+                1 => (0..2)
+                    .find(|&subslot| is_free(first_frame, subslot))
+                    .map(Capacity::Subslot)?,
+                _ => ALLOCATABLE_SLOTS.iter().rev()
+                    .filter(|&&slots| slots <= subslots.div_ceil(2))
+                    .find(|&&slots| (first_frame..first_frame + slots)
+                        .all(|frame| is_free(frame, 0) && is_free(frame, 1)))
+                    .map(|&slots| Capacity::Slots(slots))?
+            };
+            Some((delay, first_frame, capacity))
+        })?;
+
+        self.reservations.push(Reservation {
+            ssi,
+            first_frame,
+            capacity
+        });
+
+        // Larger requests remain, less what has been granted
+        let granted = match capacity {
+            Capacity::Subslot(_) => 1,
+            Capacity::Slots(slots) => slots * 2
+        };
+        match self.requests.front_mut() {
+            Some(request) if request.1 > granted => request.1 -= granted,
+            _ => { self.requests.pop_front(); }
+        }
+
+        Some(MACResourcePDU {
+            grant_is_on_current_channel: true,
+            address: downlink::Address::SSI { address: ssi },
+            slot_granting: Optional::Present(SlotGranting {
+                capacity_allocation: match capacity {
+                    Capacity::Subslot(0) => CapacityAllocation::FirstSubslot,
+                    Capacity::Subslot(_) => CapacityAllocation::SecondSubslot,
+                    Capacity::Slots(slots) => CapacityAllocation::Slots(slots)
+                },
+                granting_delay: match delay {
+                    0 => GrantingDelay::AtNextOpportunity,
+                    delay => GrantingDelay::After(delay)
+                }
+            }),
+            ..MACResourcePDU::null()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::bits::{from_bitstr, Bits};
+    use crate::codec::{Encodable, Writer};
+    use crate::pdu::uplink::{LengthOrReservationRequirement, MACAccessPDU, MACEndHUPDU};
+
+    fn capacity_request(ssi: u32, reservation_requirement: ReservationRequirement) -> UplinkMACPDU {
+        UplinkMACPDU::MACAccess(MACAccessPDU {
+            fill_bit_indication: false,
+            encrypted: false,
+            address: Address::SSI { ssi },
+            length_or_capacity_request: Some(LengthOrCapacityRequest::CapacityRequest {
+                fragmentation: false,
+                reservation_requirement
+            }),
+            tm_sdu_bits: Bits::new()
+        })
+    }
+
+    fn slot_granting(pdu: &MACResourcePDU) -> &SlotGranting {
+        match &pdu.slot_granting {
+            Optional::Present(slot_granting) => slot_granting,
+            Optional::Absent => panic!("expected slot granting")
+        }
+    }
+
+    #[test]
+    fn grants_requests_in_turn() {
+
+        let mut manager = ReservationManager::new();
+        let time = TDMATime::at(0, 3, 0, 0);
+        manager.receive(&capacity_request(1, ReservationRequirement::Slots2), &time, 0);
+        manager.receive(&capacity_request(2, ReservationRequirement::Subslot), &time, 1);
+
+        // Two slots from the next opportunity
+        let grant = manager.grant(&time, |_, _| false).unwrap();
+        assert_eq!(grant.address, downlink::Address::SSI { address: 1 });
+        assert_eq!(slot_granting(&grant), &SlotGranting {
+            capacity_allocation: CapacityAllocation::Slots(2),
+            granting_delay: GrantingDelay::AtNextOpportunity
+        });
+
+        let mut writer = Writer::new();
+        grant.encode(&mut writer);
+        assert_eq!(writer.done().len().div_ceil(8) * 8, GRANT_PDU_SIZE);
+
+        // The subslot is delayed until after them
+        let grant = manager.grant(&time, |_, _| false).unwrap();
+        assert_eq!(slot_granting(&grant), &SlotGranting {
+            capacity_allocation: CapacityAllocation::FirstSubslot,
+            granting_delay: GrantingDelay::After(2)
+        });
+        assert!(!manager.has_pending_requests());
+
+        assert_eq!(manager.reserved_for(&TDMATime::at(2, 4, 0, 0), 1), Some(1));
+        assert_eq!(manager.reserved_for(&TDMATime::at(0, 6, 0, 0), 0), Some(2));
+        assert_eq!(manager.reserved_for(&TDMATime::at(0, 6, 0, 0), 1), None);
+    }
+
+    #[test]
+    fn grants_long_requests_in_parts() {
+
+        let mut manager = ReservationManager::new();
+        let time = TDMATime::at(0, 0, 0, 0);
+        manager.request(1026, ReservationRequirement::Slots17);
+
+        // Frame 12 is unavailable, leaving only the ten slots before it free
+        let unavailable = |time: &TDMATime, _| time.frame() == 12;
+        let grant = manager.grant(&time, unavailable).unwrap();
+        let mut writer = Writer::new();
+        slot_granting(&grant).capacity_allocation.encode(&mut writer);
+        assert_eq!(writer.done(), from_bitstr("1000"));
+
+        // The MS asks for more in the last of its reserved slots, replacing what remained
+        let end = UplinkMACPDU::MACEndHU(MACEndHUPDU {
+            fill_bit_indication: false,
+            length_or_reservation_requirement: LengthOrReservationRequirement::ReservationRequirement(
+                ReservationRequirement::Slot
+            ),
+            tm_sdu_bits: Bits::new()
+        });
+        manager.receive(&end, &TDMATime::at(0, 10, 0, 0), 1);
+
+        let grant = manager.grant(&TDMATime::at(0, 10, 0, 0), |_, _| false).unwrap();
+        assert_eq!(grant.address, downlink::Address::SSI { address: 1026 });
+        assert_eq!(slot_granting(&grant), &SlotGranting {
+            capacity_allocation: CapacityAllocation::Slots(1),
+            granting_delay: GrantingDelay::AtNextOpportunity
+        });
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TDMATime {
     slot: u32,
 
@@ -54,7 +54,7 @@ impl TDMATime {
         self.multiframe + 1
     }
 
-    /// Returns the 1-based hyperframe number, between 1 and 65535
+    /// Returns the 1-based hyperframe number, between 1 and 65536
     pub fn hyperframe(&self) -> u32 {
         self.hyperframe + 1
     }
@@ -66,7 +66,7 @@ impl TDMATime {
 
     fn from_slot_number(slot_number: u32) -> Self {
 
-        let hyperframe = (slot_number / (60 * 18 * 4)) % 65535;
+        let hyperframe = (slot_number / (60 * 18 * 4)) % 65536;
         let slot_number = slot_number % (60 * 18 * 4);
         let multiframe = slot_number / (18 * 4);
         let slot_number = slot_number % (18 * 4);
@@ -81,6 +81,18 @@ impl TDMATime {
         }
     }
 
+    /// Returns the number of slots which have elapsed since `earlier`, allowing for the
+    /// hyperframe number wrapping around
+    pub fn slots_since(&self, earlier: &TDMATime) -> u32 {
+        let period = 65536 * 60 * 18 * 4;
+        (self.as_slot_number() + period - earlier.as_slot_number()) % period
+    }
+
+    /// Returns the time `frames` frames later, in the same timeslot
+    pub fn plus_frames(&self, frames: u32) -> Self {
+        TDMATime::from_slot_number(self.as_slot_number() + frames * 4)
+    }
+
     pub fn next(self) -> Self {
         let slot_number = self.as_slot_number();
         let next_slot_number = slot_number + 1;
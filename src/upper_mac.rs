@@ -1,8 +1,13 @@
+use std::collections::VecDeque;
 use crate::bits::Bits;
+use crate::cell_config::CellConfig;
 use crate::logical_channels::LogicalChannel;
 use crate::codec::{Writer, Encodable, add_fill_bits, FillBitCapacity};
 use crate::lower::scrambler::State;
-use crate::pdu;
+use crate::association::MACBlockBuilder;
+use crate::fragmentation::Fragmenter;
+use crate::reservation::{ReservationManager, GRANT_PDU_SIZE};
+use crate::pdu::uplink::UplinkMACPDU;
 use crate::tdma_time::TDMATime;
 use crate::pdu::downlink::*;
 
@@ -19,13 +24,86 @@ pub(crate) struct TMVUnitDataChannel {
     pub(crate) scrambling_code: State
 }
 
+/// Content of the second half of a traffic slot when the first half has been stolen
+pub(crate) enum SecondHalfSlot {
+    /// The second half-slot is also stolen, carrying the provided STCH MAC block
+    Stolen(Bits),
+    /// The second half-slot continues to carry the provided 216 bits of traffic
+    Traffic(Bits)
+}
+
+/// Capacity of a MAC block on the SCH/HD, BNCH or STCH
+const HALF_SLOT_CAPACITY: usize = 124;
+
+/// Capacity of a MAC block on the SCH/F
+const FULL_SLOT_CAPACITY: usize = 268;
+
+/// A TM-SDU waiting to be sent on the downlink
+struct QueuedSignalling {
+    priority: u8,
+    pdu: MACResourcePDU
+}
+
 /// The state of the Upper MAC
-pub struct UpperMAC { }
+pub struct UpperMAC {
+    config: CellConfig,
+    /// TM-SDUs waiting to be sent, highest priority first
+    signalling: VecDeque<QueuedSignalling>,
+    /// The TM-SDU currently being sent in fragments, if any
+    fragmenter: Option<Fragmenter>,
+    /// Uplink capacity requested by and reserved for MSs on the MCCH
+    reservations: ReservationManager
+}
 
 impl UpperMAC {
 
-    pub fn new() -> Self {
-        UpperMAC {}
+    pub fn new(config: CellConfig) -> Self {
+        UpperMAC {
+            config,
+            signalling: VecDeque::new(),
+            fragmenter: None,
+            reservations: ReservationManager::new()
+        }
+    }
+
+    /// Queue a TM-SDU to be sent on the MCCH, addressed to an SSI or event label. TM-SDUs with a
+    /// higher priority are sent first, otherwise they're sent in the order queued.
+    pub(crate) fn queue_signalling(&mut self, address: Address, priority: u8, tm_sdu_bits: Bits) {
+
+        let queued = QueuedSignalling {
+            priority,
+            pdu: MACResourcePDU {
+                address,
+                tm_sdu_bits,
+                ..MACResourcePDU::null()
+            }
+        };
+
+        match self.signalling.iter().position(|other| other.priority < priority) {
+            Some(index) => self.signalling.insert(index, queued),
+            None => self.signalling.push_back(queued)
+        }
+    }
+
+    /// Whether any signalling remains to be sent
+    pub(crate) fn has_pending_signalling(&self) -> bool {
+        self.fragmenter.is_some() || !self.signalling.is_empty()
+    }
+
+    /// Handle a PDU received on the MCCH uplink at `time`, in `subslot` (0 for a full slot),
+    /// recording any capacity it requests to be granted on the downlink
+    pub(crate) fn receive_uplink(&mut self, pdu: &UplinkMACPDU, time: &TDMATime, subslot: usize) {
+        self.reservations.receive(pdu, time, subslot);
+    }
+
+    /// The configuration of the cell
+    pub(crate) fn config(&self) -> &CellConfig {
+        &self.config
+    }
+
+    /// The scrambling code used for every channel other than the BSCH
+    pub(crate) fn scrambling_code(&self) -> State {
+        self.config.scrambling_code()
     }
 
     /// Should the BNCH be mapped?
@@ -40,20 +118,46 @@ impl UpperMAC {
         time.is_control_frame() && (time.multiframe() + time.slot()) % 4 == 3
     }
 
-    /// Just a dummy for now to get us by...
-    fn generate_control_aach(&self, time: &TDMATime) -> Bits {
+    /// Should signalling be scheduled? Only the MCCH, on timeslot 1, carries it
+    fn slot_is_mcch(&self, time: &TDMATime) -> bool {
+        time.slot() == 1
+    }
+
+    /// Should the first uplink subslot be reserved for the CLCH?
+    fn slot_should_grant_clch(time: &TDMATime) -> bool {
+        // During the control frame, the CLCH is offered where (MN + TN) % 4 = 3, as for the BSCH
+        time.is_control_frame() && (time.multiframe() + time.slot()) % 4 == 3
+    }
 
+    /// Is `subslot` of the uplink slot at `time` reserved for an MS?
+    fn subslot_is_reserved(&self, time: &TDMATime, subslot: usize) -> bool {
+        self.slot_is_mcch(time) && self.reservations.reserved_for(time, subslot).is_some()
+    }
+
+    /// The ACCESS-ASSIGN for the downlink slot at `time`, signalling for each subslot of the
+    /// corresponding uplink slot whether it's offered for the CLCH, reserved by the reservation
+    /// manager, or open for random access
+    pub(crate) fn generate_access_assign(&self, time: &TDMATime) -> AccessAssign {
+
+        // The first subslot may be reserved for linearisation, or for an MS granted it
         let access_field_1 = AccessField {
             access_code: AccessCode::AccessCodeA,
-            base_frame_length: BaseFrameLength::OngoingFrame
+            base_frame_length: match (Self::slot_should_grant_clch(time), self.subslot_is_reserved(time, 0)) {
+                (true, _) => BaseFrameLength::CLCHSubslot,
+                (false, true) => BaseFrameLength::ReservedSubslot,
+                (false, false) => BaseFrameLength::OngoingFrame
+            }
         };
 
         let access_field_2 = AccessField {
             access_code: AccessCode::AccessCodeB,
-            base_frame_length: BaseFrameLength::OngoingFrame
+            base_frame_length: match self.subslot_is_reserved(time, 1) {
+                true => BaseFrameLength::ReservedSubslot,
+                false => BaseFrameLength::OngoingFrame
+            }
         };
 
-        let access_assign = match time.is_control_frame() {
+        match time.is_control_frame() {
             true => AccessAssign::ControlFrame(AccessAssignControlFrame::UplinkCommonOnly {
                 access_field_1,
                 access_field_2
@@ -63,28 +167,88 @@ impl UpperMAC {
                 access_field_1,
                 access_field_2
             })
-        };
+        }
+    }
 
+    fn generate_control_aach(&self, time: &TDMATime) -> Bits {
         let mut writer = Writer::new();
-        access_assign.encode(&mut writer);
+        self.generate_access_assign(time).encode(&mut writer);
         writer.done()
     }
 
-    /// Generate a half-slot with no content
+    /// Generate a half-slot with no content, I.e. just a Null PDU
     fn generate_null_sch_hd(&self) -> Bits {
+        MACBlockBuilder::new(HALF_SLOT_CAPACITY).finish()
+    }
 
-        let null_pdu = pdu::downlink::MACResourcePDU::null();
-        let mut writer = Writer::new();
-        null_pdu.encode(&mut writer);
-        let mut null_pdu_bits = writer.done();
+    /// Generate a half-slot, carrying signalling if the slot is on the MCCH
+    fn generate_sch_hd(&mut self, time: &TDMATime) -> Bits {
+        match self.slot_is_mcch(time) {
+            true => self.generate_signalling_block(HALF_SLOT_CAPACITY, time),
+            false => self.generate_null_sch_hd()
+        }
+    }
 
-        // Fill bits
-        add_fill_bits(&mut null_pdu_bits, FillBitCapacity::Bits(124));
+    /// Does the pending signalling need a full slot? This is the case while a TM-SDU is being
+    /// fragmented, or if the next TM-SDU doesn't fit in a half-slot.
+    fn signalling_needs_full_slot(&self) -> bool {
+        match (&self.fragmenter, self.signalling.front()) {
+            (Some(_), _) => true,
+            (None, Some(queued)) => {
+                let mut writer = Writer::new();
+                queued.pdu.encode(&mut writer);
+                writer.done().len() > HALF_SLOT_CAPACITY
+            },
+            (None, None) => false
+        }
+    }
+
+    /// Generate a MAC block of `capacity` bits, granting reserved capacity and continuing any
+    /// fragmented TM-SDU before associating as many queued TM-SDUs as fit. A TM-SDU too long for
+    /// an empty block is fragmented.
+    fn generate_signalling_block(&mut self, capacity: usize, time: &TDMATime) -> Bits {
 
-        null_pdu_bits
+        let mut builder = MACBlockBuilder::new(capacity);
+
+        while builder.remaining() >= GRANT_PDU_SIZE && self.reservations.has_pending_requests() {
+            let clch = |time: &TDMATime, subslot| subslot == 0 && Self::slot_should_grant_clch(time);
+            let Some(grant) = self.reservations.grant(time, clch) else {
+                break;
+            };
+            builder.push(grant).expect("a grant fits in the remaining capacity");
+        }
+
+        if let Some(fragmenter) = &mut self.fragmenter {
+            builder.push_fragment(fragmenter);
+            if !fragmenter.is_complete() {
+                return builder.finish();
+            }
+            self.fragmenter = None;
+        }
+
+        while let Some(queued) = self.signalling.pop_front() {
+            match builder.push(queued.pdu) {
+                Ok(()) => continue,
+                Err(pdu) if builder.remaining() == capacity => {
+                    let mut fragmenter = Fragmenter::new(pdu);
+                    builder.push_fragment(&mut fragmenter);
+                    self.fragmenter = Some(fragmenter);
+                    break;
+                },
+                Err(pdu) => {
+                    self.signalling.push_front(QueuedSignalling {
+                        priority: queued.priority,
+                        pdu
+                    });
+                    break;
+                }
+            }
+        }
+
+        builder.finish()
     }
 
-    pub fn generate_slot(&self, time: &TDMATime) -> TMVUnitData {
+    pub fn generate_slot(&mut self, time: &TDMATime) -> TMVUnitData {
 
         // Broadcast Network Channel mapped in this slot?
         if self.slot_should_be_bnch(time) {
@@ -93,12 +257,12 @@ impl UpperMAC {
                 primary: TMVUnitDataChannel {
                     mac_block: bnch_bits,
                     logical_channel: LogicalChannel::BroadcastNetwork,
-                    scrambling_code: State::new(0, 0, 0), // todo: configuration?
+                    scrambling_code: self.scrambling_code(),
                 },
                 secondary: Some(TMVUnitDataChannel {
-                    mac_block: self.generate_null_sch_hd(),
+                    mac_block: self.generate_sch_hd(time),
                     logical_channel: LogicalChannel::SignallingHalfDownlink,
-                    scrambling_code: State::zero()
+                    scrambling_code: self.scrambling_code()
                 }),
                 aach: self.generate_control_aach(time),
             }
@@ -114,66 +278,108 @@ impl UpperMAC {
                     scrambling_code: State::zero()
                 },
                 secondary: Some(TMVUnitDataChannel {
-                    mac_block: self.generate_null_sch_hd(),
+                    mac_block: self.generate_sch_hd(time),
                     logical_channel: LogicalChannel::SignallingHalfDownlink,
-                    scrambling_code: State::zero()
+                    scrambling_code: self.scrambling_code()
                 }),
                 aach: self.generate_control_aach(time),
             }
         }
 
-        // Placeholder: generate two empty half-slots
-        // This behaviour is specified for frames with no signalling
-        let null_sch_hd_bits = self.generate_null_sch_hd();
+        // Signalling which doesn't fit in a half-slot is sent on the SCH/F
+        if self.slot_is_mcch(time) && self.signalling_needs_full_slot() {
+            return TMVUnitData {
+                primary: TMVUnitDataChannel {
+                    mac_block: self.generate_signalling_block(FULL_SLOT_CAPACITY, time),
+                    logical_channel: LogicalChannel::SignallingFull,
+                    scrambling_code: self.scrambling_code()
+                },
+                secondary: None,
+                aach: self.generate_control_aach(time),
+            }
+        }
+
+        // Otherwise two half-slots, which are empty if there's no signalling
         TMVUnitData {
             primary: TMVUnitDataChannel {
-                mac_block: null_sch_hd_bits.clone(),
+                mac_block: self.generate_sch_hd(time),
                 logical_channel: LogicalChannel::SignallingHalfDownlink,
-                scrambling_code: State::new(0, 0, 0)
+                scrambling_code: self.scrambling_code()
             },
             secondary: Some(TMVUnitDataChannel {
-                mac_block: null_sch_hd_bits.clone(),
+                mac_block: self.generate_sch_hd(time),
                 logical_channel: LogicalChannel::SignallingHalfDownlink,
-                scrambling_code: State::new(0, 0, 0)
+                scrambling_code: self.scrambling_code()
             }),
             aach: self.generate_control_aach(time),
         }
     }
 
+    /// Generate a traffic slot where the first (or both) half-slots are stolen for signalling.
+    /// If both half-slots are stolen, the first half-slot's MAC block should indicate this in its
+    /// length indication.
+    pub fn generate_stolen_slot(&self, time: &TDMATime, first_half: Bits, second_half: SecondHalfSlot) -> TMVUnitData {
+
+        let mut stch_bits = first_half;
+        add_fill_bits(&mut stch_bits, FillBitCapacity::Bits(124));
+
+        let secondary = match second_half {
+            SecondHalfSlot::Stolen(mut second_stch_bits) => {
+                add_fill_bits(&mut second_stch_bits, FillBitCapacity::Bits(124));
+                TMVUnitDataChannel {
+                    mac_block: second_stch_bits,
+                    logical_channel: LogicalChannel::Stealing,
+                    scrambling_code: self.scrambling_code()
+                }
+            },
+            SecondHalfSlot::Traffic(traffic_bits) => TMVUnitDataChannel {
+                mac_block: traffic_bits,
+                logical_channel: LogicalChannel::TrafficUnprotected,
+                scrambling_code: self.scrambling_code()
+            }
+        };
+
+        TMVUnitData {
+            primary: TMVUnitDataChannel {
+                mac_block: stch_bits,
+                logical_channel: LogicalChannel::Stealing,
+                scrambling_code: self.scrambling_code()
+            },
+            secondary: Some(secondary),
+            aach: self.generate_control_aach(time),
+        }
+    }
+
     /// Generate the BNCH
-    /// todo: This will be outsourced to a helper that maintains state for the optional fields,
-    /// todo: accesses configuration etc.
     fn generate_bnch(&self) -> Bits {
 
+        let config = &self.config;
+
+        // The MLE SYSINFO PDU is carried as the TM-SDU
         let mut writer = Writer::new();
+        MLESysinfoPDU {
+            location_area: config.location_area,
+            subscriber_class: config.subscriber_class,
+            bs_service_details: config.bs_service_details.clone()
+        }.encode(&mut writer);
+        let tm_sdu_bits = writer.done();
 
         let sysinfo_pdu = Sysinfo {
-            main_carrier: 0,
-            frequency_band: 0,
-            offset: Offset::NoOffset,
-            duplex_spacing: 0,
-            reverse: false,
+            main_carrier: config.main_carrier,
+            frequency_band: config.frequency_band,
+            offset: config.offset.clone(),
+            duplex_spacing: config.duplex_spacing,
+            reverse: config.reverse_operation,
             number_of_common_scch: NumberOfCommonSCCH::None,
-            rf_parameters: RFParameters {
-                ms_txpwr_max_cell: 0,
-                rxlev_access_min: 0,
-                access_parameter: 0,
-                radio_downlink_timeout: 0
-            },
+            rf_parameters: config.rf_parameters.clone(),
             hyperframe_or_cipher_key: HyperframeOrCipherKey::Hyperframe {
                 hyperframe_number: 0
             },
-            optional_field: OptionalField::DefaultAccessCodeA(AccessCodeDefinition {
-                immediate: Immediate::AlwaysRandomise,
-                waiting_time_opportunities: 0,
-                number_of_attempts: 0,
-                frame_length_x4: false,
-                timeslot: TimeslotPointer::SameAsDownlink,
-                minimum_priority: 0,
-            }),
-            tm_sdu_bits: Bits::repeat(false, 42)
+            optional_field: config.sysinfo_optional_field.clone(),
+            tm_sdu_bits
         };
 
+        let mut writer = Writer::new();
         sysinfo_pdu.encode(&mut writer);
         let mut bnch_bits = writer.done();
 
@@ -188,8 +394,8 @@ impl UpperMAC {
         let mut writer = Writer::new();
 
         let sync_tm_sdu = MLESyncPDU {
-            mcc: 234,
-            mnc: 0,
+            mcc: self.config.mcc,
+            mnc: self.config.mnc,
             neighbour_cell_broadcast: NeighbourCellBroadcast {
                 d_nwrk_broadcast_supported: true,
                 d_nwrk_enquiry_supported: false,
@@ -204,11 +410,12 @@ impl UpperMAC {
 
         let sync_pdu = Sync {
             system_code: 0,
-            colour_code: 0,
-            timeslot_number: time.slot(),
+            colour_code: self.config.colour_code,
+            // Coded 0-3 for timeslots 1-4
+            timeslot_number: time.slot() - 1,
             frame_number: time.frame(),
             multiframe_number: time.multiframe(),
-            sharing_mode: SharingMode::ContinuousTransmission,
+            sharing_mode: self.config.sharing_mode.clone(),
             ts_reserved_frames: TSReservedFrames::Reserve1,
             u_plane_dtx: false,
             frame_18_extension: false,
@@ -225,3 +432,200 @@ impl UpperMAC {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::codec::{Decodable, Reader};
+    use crate::codec::Optional;
+    use crate::fragmentation::{Fragment, Reassembler};
+    use crate::pdu::uplink::{self, LengthOrCapacityRequest, MACAccessPDU, ReservationRequirement};
+
+    fn config() -> CellConfig {
+        CellConfig {
+            mcc: 235,
+            mnc: 91,
+            colour_code: 12,
+            main_carrier: 1521,
+            location_area: 42,
+            ..CellConfig::default()
+        }
+    }
+
+    #[test]
+    fn broadcasts_configured_identity() {
+
+        let mut mac = UpperMAC::new(config());
+
+        // BSCH in timeslot 2 of frame 18, multiframe 1
+        let blocks = mac.generate_slot(&TDMATime::at(1, 17, 0, 0));
+        assert!(matches!(blocks.primary.logical_channel, LogicalChannel::BroadcastSynchronisation));
+
+        let sync = Sync::decode(&mut Reader::new(&blocks.primary.mac_block)).unwrap();
+        let mle_sync = MLESyncPDU::decode(&mut Reader::new(&sync.tm_sdu_bits)).unwrap();
+        assert_eq!((mle_sync.mcc, mle_sync.mnc, sync.colour_code), (235, 91, 12));
+
+        // Everything but the BSCH is scrambled using the identity
+        assert_eq!(blocks.primary.scrambling_code.state, State::zero().state);
+        assert_eq!(blocks.secondary.unwrap().scrambling_code.state, State::new(235, 91, 12).state);
+    }
+
+    #[test]
+    fn broadcasts_configured_sysinfo() {
+
+        let mut mac = UpperMAC::new(config());
+
+        // BNCH in timeslot 4 of frame 18, multiframe 1
+        let blocks = mac.generate_slot(&TDMATime::at(3, 17, 0, 0));
+        assert!(matches!(blocks.primary.logical_channel, LogicalChannel::BroadcastNetwork));
+
+        let sysinfo = Sysinfo::decode(&mut Reader::new(&blocks.primary.mac_block)).unwrap();
+        assert_eq!(sysinfo.main_carrier, 1521);
+
+        let mle_sysinfo = MLESysinfoPDU::decode(&mut Reader::new(&sysinfo.tm_sdu_bits)).unwrap();
+        assert_eq!(mle_sysinfo.location_area, 42);
+    }
+
+    #[test]
+    fn schedules_signalling_by_priority() {
+
+        let mut mac = UpperMAC::new(config());
+        mac.queue_signalling(Address::SSI { address: 1 }, 0, Bits::repeat(true, 5));
+        mac.queue_signalling(Address::SSI { address: 2 }, 1, Bits::repeat(true, 5));
+
+        // Only the MCCH carries signalling
+        let blocks = mac.generate_slot(&TDMATime::at(1, 3, 0, 0));
+        let pdus = DownlinkMACPDU::dissociate(&blocks.primary.mac_block).unwrap();
+        assert!(matches!(pdus[0], DownlinkMACPDU::MACResource(MACResourcePDU { address: Address::NullPDU, .. })));
+
+        // Both fit in the first half-slot, the higher priority first
+        let blocks = mac.generate_slot(&TDMATime::at(0, 3, 0, 0));
+        assert!(matches!(blocks.primary.logical_channel, LogicalChannel::SignallingHalfDownlink));
+        let addresses: Vec<Address> = DownlinkMACPDU::dissociate(&blocks.primary.mac_block)
+            .unwrap()
+            .into_iter()
+            .filter_map(|pdu| match pdu {
+                DownlinkMACPDU::MACResource(pdu) => Some(pdu.address),
+                _ => None
+            })
+            .collect();
+        assert_eq!(addresses, [Address::SSI { address: 2 }, Address::SSI { address: 1 }, Address::NullPDU]);
+        assert!(!mac.has_pending_signalling());
+    }
+
+    #[test]
+    fn fragments_long_signalling_over_full_slots() {
+
+        let mut mac = UpperMAC::new(config());
+        let tm_sdu_bits: Bits = (0..600).map(|index| index % 3 == 0).collect();
+        mac.queue_signalling(Address::SSI { address: 1026 }, 0, tm_sdu_bits.clone());
+
+        let mut reassembler = Reassembler::new(18 * 4);
+        let mut reassembled = None;
+        let mut frame = 0;
+
+        while mac.has_pending_signalling() {
+            let time = TDMATime::at(0, frame, 0, 0);
+            let blocks = mac.generate_slot(&time);
+            assert!(matches!(blocks.primary.logical_channel, LogicalChannel::SignallingFull));
+
+            for pdu in DownlinkMACPDU::dissociate(&blocks.primary.mac_block).unwrap() {
+                if let Some(fragment) = Fragment::of(pdu) {
+                    reassembled = reassembler.receive(Address::SSI { address: 1026 }, fragment, &time).unwrap();
+                }
+            }
+            frame += 1;
+        }
+
+        assert_eq!(frame, 3);
+        assert_eq!(reassembled, Some(tm_sdu_bits));
+    }
+
+    #[test]
+    fn grants_requested_capacity() {
+
+        let mut mac = UpperMAC::new(config());
+        let request = UplinkMACPDU::MACAccess(MACAccessPDU {
+            fill_bit_indication: false,
+            encrypted: false,
+            address: uplink::Address::SSI { ssi: 1026 },
+            length_or_capacity_request: Some(LengthOrCapacityRequest::CapacityRequest {
+                fragmentation: false,
+                reservation_requirement: ReservationRequirement::Subslot
+            }),
+            tm_sdu_bits: Bits::new()
+        });
+        mac.receive_uplink(&request, &TDMATime::at(0, 3, 0, 0), 1);
+
+        // The grant is sent on the MCCH
+        let blocks = mac.generate_slot(&TDMATime::at(0, 3, 0, 0));
+        let pdus = DownlinkMACPDU::dissociate(&blocks.primary.mac_block).unwrap();
+        let DownlinkMACPDU::MACResource(grant) = &pdus[0] else {
+            panic!("expected a MAC-RESOURCE, got {pdus:?}");
+        };
+        assert_eq!(grant.address, Address::SSI { address: 1026 });
+        assert_eq!(grant.slot_granting, Optional::Present(SlotGranting {
+            capacity_allocation: CapacityAllocation::FirstSubslot,
+            granting_delay: GrantingDelay::AtNextOpportunity
+        }));
+
+        // And the subslot is marked reserved in the following frame
+        let access_assign = mac.generate_access_assign(&TDMATime::at(0, 4, 0, 0));
+        let [Some(first), Some(second)] = access_assign.access_fields() else {
+            panic!("expected two access fields");
+        };
+        assert_eq!(first.base_frame_length, BaseFrameLength::ReservedSubslot);
+        assert_eq!(second.base_frame_length, BaseFrameLength::OngoingFrame);
+    }
+
+    #[test]
+    fn does_not_grant_clch_subslot() {
+
+        let mut mac = UpperMAC::new(config());
+        let request = |ssi| UplinkMACPDU::MACAccess(MACAccessPDU {
+            fill_bit_indication: false,
+            encrypted: false,
+            address: uplink::Address::SSI { ssi },
+            length_or_capacity_request: Some(LengthOrCapacityRequest::CapacityRequest {
+                fragmentation: false,
+                reservation_requirement: ReservationRequirement::Subslot
+            }),
+            tm_sdu_bits: Bits::new()
+        });
+
+        // Just before frame 18 of multiframe 2, where the first subslot of the MCCH is the CLCH
+        let time = TDMATime::at(0, 16, 1, 0);
+        mac.receive_uplink(&request(1026), &time, 1);
+        mac.receive_uplink(&request(1027), &time, 1);
+
+        let blocks = mac.generate_slot(&time);
+        let pdus = DownlinkMACPDU::dissociate(&blocks.primary.mac_block).unwrap();
+        let grants: Vec<&SlotGranting> = pdus.iter()
+            .filter_map(|pdu| match pdu {
+                DownlinkMACPDU::MACResource(MACResourcePDU { slot_granting: Optional::Present(granting), .. }) =>
+                    Some(granting),
+                _ => None
+            })
+            .collect();
+
+        // Only the second subslot is free in frame 18, so the other request waits a frame
+        assert_eq!(grants, [
+            &SlotGranting {
+                capacity_allocation: CapacityAllocation::SecondSubslot,
+                granting_delay: GrantingDelay::AtNextOpportunity
+            },
+            &SlotGranting {
+                capacity_allocation: CapacityAllocation::FirstSubslot,
+                granting_delay: GrantingDelay::After(1)
+            }
+        ]);
+
+        let access_assign = mac.generate_access_assign(&TDMATime::at(0, 17, 1, 0));
+        let [Some(first), Some(second)] = access_assign.access_fields() else {
+            panic!("expected two access fields");
+        };
+        assert_eq!(first.base_frame_length, BaseFrameLength::CLCHSubslot);
+        assert_eq!(second.base_frame_length, BaseFrameLength::ReservedSubslot);
+    }
+}